@@ -178,6 +178,8 @@ pub mod convert {
 /// Module tree_mutator contains functions that mutate the tree.
 pub mod tree_mutator {
 
+    use std::path::Path;
+
     use log::{debug, error};
     use tree_sitter::{InputEdit, Language, Parser, Tree};
 
@@ -224,6 +226,25 @@ pub mod tree_mutator {
 
         Some(parser)
     }
+
+    /// Get the language id of a file by its extension, for files that are not opened by the client.
+    pub fn get_language_id_by_path(path: &Path) -> Option<String> {
+        let language_id = match path.extension()?.to_str()? {
+            "c" | "h" => "c",
+            "cpp" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => "cpp",
+            "rs" => "rust",
+            "py" => "python",
+            "js" | "mjs" | "cjs" | "jsx" => "javascript",
+            "ts" => "typescript",
+            "go" => "go",
+            "cu" | "cuh" => "cuda",
+            "kt" | "kts" => "kotlin",
+            "glsl" | "vert" | "frag" | "geom" | "comp" => "glsl",
+            "ha" => "hare",
+            _ => return None,
+        };
+        Some(language_id.to_string())
+    }
 }
 
 /// Module types contains useful types for representing the source code.
@@ -241,6 +262,10 @@ pub mod types {
         // children could be None or multiple symbols
         pub children: Option<Vec<Symbol>>,
         pub belongs_to_scopes: Vec<Range>,
+
+        // the whole definition node and its signature, only known for definitions
+        pub definition_range: Option<Range>,
+        pub detail: Option<String>,
     }
 
     impl Symbol {
@@ -257,6 +282,8 @@ pub mod types {
                 },
                 children: None,
                 belongs_to_scopes: vec![],
+                definition_range: None,
+                detail: None,
             }
         }
 
@@ -284,3 +311,63 @@ pub mod tree_walker {
             .named_descendant_for_point_range(point, point)
     }
 }
+
+/// Module fuzzy contains the matcher used to filter and rank candidates by what the user typed.
+pub mod fuzzy {
+
+    /// Match the pattern as a case-insensitive subsequence of the candidate.
+    /// Return None if it doesn't match, a score otherwise, the higher the better.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use helper::fuzzy::fuzzy_match;
+    ///
+    /// assert!(fuzzy_match("gdef", "goto_definition").is_some());
+    /// assert!(fuzzy_match("fed", "goto_definition").is_none());
+    /// ```
+    pub fn fuzzy_match(pattern: &str, candidate: &str) -> Option<i64> {
+        let pattern: Vec<char> = pattern.chars().flat_map(char::to_lowercase).collect();
+        let original: Vec<char> = candidate.chars().collect();
+        let lowercase: Vec<char> = original
+            .iter()
+            .map(|c| c.to_lowercase().next().unwrap_or(*c))
+            .collect();
+
+        let mut score: i64 = 0;
+        let mut next = 0;
+        let mut previous_match: Option<usize> = None;
+        for p in &pattern {
+            let position = (next..lowercase.len()).find(|i| lowercase[*i] == *p)?;
+
+            score += 1;
+            if previous_match.map(|previous| previous + 1) == Some(position) {
+                // consecutive characters
+                score += 5;
+            } else if position == 0
+                || !original[position - 1].is_alphanumeric()
+                || (original[position - 1].is_lowercase() && original[position].is_uppercase())
+            {
+                // start of a word, e.g. `snake_case` or `camelCase`
+                score += 3;
+            } else {
+                // skipped characters
+                score -= (position - next).min(3) as i64;
+            }
+
+            previous_match = Some(position);
+            next = position + 1;
+        }
+
+        if lowercase.starts_with(&pattern) {
+            score += 10;
+            if lowercase.len() == pattern.len() {
+                score += 10;
+            }
+        }
+
+        // prefer shorter candidates
+        score -= (lowercase.len() - pattern.len()) as i64 / 4;
+
+        Some(score)
+    }
+}
@@ -4,6 +4,7 @@ use helper::{
         lsp_position_to_ts_point, lsp_range_to_ts_range, offset_to_position, position_to_offset,
         ts_point_to_lsp_position, ts_range_to_lsp_range,
     },
+    fuzzy::fuzzy_match,
 };
 use lsp_types::Position;
 use tree_sitter::Point;
@@ -56,3 +57,21 @@ fn ts_range_and_lsp_range() {
     assert_eq!(ts_range_to_lsp_range(&ts_range), lsp_range);
     assert_eq!(lsp_range_to_ts_range(&lsp_range, text.as_bytes()), ts_range);
 }
+
+#[test]
+fn fuzzy_match_ranking() {
+    // case-insensitive subsequence
+    assert!(fuzzy_match("bDS", "build_definitions_and_scopes").is_some());
+    assert!(fuzzy_match("scopes_build", "build_definitions_and_scopes").is_none());
+    assert!(fuzzy_match("", "anything").is_some());
+
+    // prefix beats word starts, word starts beat scattered characters
+    let prefix = fuzzy_match("get", "get_parser").unwrap();
+    let word_starts = fuzzy_match("get", "GlobalEditTree").unwrap();
+    let scattered = fuzzy_match("get", "language_identity").unwrap();
+    assert!(prefix > word_starts);
+    assert!(word_starts > scattered);
+
+    // exact match beats a longer prefix match
+    assert!(fuzzy_match("symbol", "Symbol").unwrap() > fuzzy_match("symbol", "symbols").unwrap());
+}
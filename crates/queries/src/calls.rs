@@ -0,0 +1,32 @@
+use tree_sitter::{Node, Range};
+
+use crate::{capture_by_query_source, utils::get_query_source};
+
+pub const CALL_NAME: &str = "call.name";
+
+/// Get the callee name and its range of every call under the node, from the `calls` query.
+pub fn build_call_sites(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+) -> Vec<(String, Range)> {
+    let query_source = match get_query_source(language_id, "calls") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut call_sites: Vec<(String, Range)> =
+        capture_by_query_source(source_code, *node, &query_source)
+            .into_iter()
+            .filter(|(capture_name, _)| capture_name == CALL_NAME)
+            .map(|(_, node)| {
+                (
+                    node.utf8_text(source_code).unwrap_or_default().to_string(),
+                    node.range(),
+                )
+            })
+            .collect();
+    call_sites.dedup_by_key(|(_, range)| range.start_byte);
+
+    call_sites
+}
@@ -0,0 +1,201 @@
+use std::{cmp::Reverse, collections::HashSet};
+
+use helper::types::Symbol;
+use tree_sitter::{Node, Range};
+
+use crate::{highlight::get_kind, match_by_query_source, utils::get_query_source};
+
+pub const NAME: &str = ".name";
+pub const WHOLE: &str = ".whole";
+
+/// Build the outline of the document from the `children` query.
+///
+/// Each pattern captures the name of a definition as `@<kind>.name` and the whole definition
+/// as `@<kind>.whole`, where kind is one of the highlight capture names. Definitions are nested
+/// by containment, e.g. fields become children of the struct they are declared in.
+pub fn build_document_symbols(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+) -> Vec<Symbol> {
+    let query_source = match get_query_source(language_id, "children") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut symbols: Vec<Symbol> = Vec::new();
+    // the same definition can be matched by more than one pattern
+    let mut visited: HashSet<(usize, usize, usize)> = HashSet::new();
+
+    for captures in match_by_query_source(source_code, *node, &query_source) {
+        let name = captures.iter().find(|(capture, _)| capture.ends_with(NAME));
+        let whole = captures
+            .iter()
+            .find(|(capture, _)| capture.ends_with(WHOLE));
+        let ((capture_name, name_node), (_, whole_node)) = match (name, whole) {
+            (Some(name), Some(whole)) => (name, whole),
+            _ => continue,
+        };
+
+        if !visited.insert((
+            name_node.start_byte(),
+            whole_node.start_byte(),
+            whole_node.end_byte(),
+        )) {
+            continue;
+        }
+
+        let (completion_kind, symbol_kind) =
+            get_kind(capture_name.trim_end_matches(NAME).to_string());
+
+        symbols.push(Symbol {
+            name: name_node
+                .utf8_text(source_code)
+                .unwrap_or_default()
+                .to_string(),
+            completion_kind: vec![completion_kind],
+            symbol_kind: vec![symbol_kind],
+            location: name_node.range(),
+            children: None,
+            belongs_to_scopes: vec![],
+            definition_range: Some(whole_node.range()),
+            detail: Some(get_signature(source_code, whole_node)),
+        });
+    }
+
+    nest_by_containment(symbols)
+}
+
+/// Get the signature of a definition, the text before its body with whitespace collapsed.
+pub fn get_signature(source_code: &[u8], node: &Node) -> String {
+    let end_byte = match find_body(*node) {
+        Some(body) => body.start_byte(),
+        None => node.end_byte(),
+    };
+    let text = String::from_utf8_lossy(&source_code[node.start_byte()..end_byte]);
+
+    // anonymous bodies, e.g. `typedef struct { ... } name;`
+    let text = text.split('{').next().unwrap_or_default();
+
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_end_matches(|c: char| c.is_whitespace() || "{;:=".contains(c))
+        .to_string()
+}
+
+/// Get the documentation of a definition, the comments right above it or the docstring which
+/// starts its body, without comment markers.
+pub fn get_documentation(source_code: &[u8], node: &Node) -> Option<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+    // e.g. `#[derive(Debug)]` between the documentation and the definition
+    while let Some(attribute) = sibling.filter(|sibling| {
+        sibling.kind().contains("attribute") || sibling.kind().contains("decorator")
+    }) {
+        row = attribute.start_position().row;
+        sibling = attribute.prev_sibling();
+    }
+    while let Some(comment) = sibling.filter(|sibling| sibling.kind().contains("comment")) {
+        // a blank line ends the documentation
+        if comment.end_position().row + 1 < row {
+            break;
+        }
+        let text = String::from_utf8_lossy(&source_code[comment.start_byte()..comment.end_byte()]);
+        for line in text.lines().rev() {
+            lines.push(strip_comment_markers(line));
+        }
+        row = comment.start_position().row;
+        sibling = comment.prev_sibling();
+    }
+    lines.reverse();
+
+    // python
+    if lines.is_empty() {
+        let docstring = find_body(*node)
+            .and_then(|body| body.named_child(0))
+            .filter(|statement| statement.kind() == "expression_statement")
+            .and_then(|statement| statement.named_child(0))
+            .filter(|string| string.kind() == "string");
+        if let Some(docstring) = docstring {
+            let text =
+                String::from_utf8_lossy(&source_code[docstring.start_byte()..docstring.end_byte()]);
+            lines.extend(
+                text.trim_matches(|c| c == '"' || c == '\'')
+                    .lines()
+                    .map(|line| line.trim().to_string()),
+            );
+        }
+    }
+
+    let documentation = lines.join("\n").trim().to_string();
+    if documentation.is_empty() {
+        None
+    } else {
+        Some(documentation)
+    }
+}
+
+fn strip_comment_markers(line: &str) -> String {
+    line.trim()
+        .trim_start_matches("/**")
+        .trim_start_matches("/*")
+        .trim_end_matches("*/")
+        .trim_start_matches(['/', '#', '*', '!'])
+        .trim()
+        .to_string()
+}
+
+fn find_body(node: Node) -> Option<Node> {
+    if let Some(body) = node.child_by_field_name("body") {
+        return Some(body);
+    }
+    (0..node.named_child_count()).find_map(|i| node.named_child(i).and_then(find_body))
+}
+
+/// Nest symbols by the range of their definitions, the input is not required to be ordered.
+fn nest_by_containment(mut symbols: Vec<Symbol>) -> Vec<Symbol> {
+    symbols.sort_by_key(|symbol| {
+        let range = whole_range(symbol);
+        (range.start_byte, Reverse(range.end_byte))
+    });
+
+    let mut roots: Vec<Symbol> = Vec::new();
+    let mut stack: Vec<Symbol> = Vec::new();
+
+    for symbol in symbols {
+        // close every open symbol that doesn't contain this one
+        while let Some(parent) = stack.last() {
+            if contains(&whole_range(parent), &whole_range(&symbol)) {
+                break;
+            }
+            let closed = stack.pop().unwrap();
+            attach(closed, &mut stack, &mut roots);
+        }
+        stack.push(symbol);
+    }
+    while let Some(closed) = stack.pop() {
+        attach(closed, &mut stack, &mut roots);
+    }
+
+    roots
+}
+
+fn attach(symbol: Symbol, stack: &mut [Symbol], roots: &mut Vec<Symbol>) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.get_or_insert_with(Vec::new).push(symbol),
+        None => roots.push(symbol),
+    }
+}
+
+fn whole_range(symbol: &Symbol) -> Range {
+    symbol.definition_range.unwrap_or(symbol.location)
+}
+
+/// Sibling definitions may share the same node, e.g. `int a, b;`
+fn contains(parent: &Range, child: &Range) -> bool {
+    parent.start_byte <= child.start_byte
+        && child.end_byte <= parent.end_byte
+        && (parent.start_byte, parent.end_byte) != (child.start_byte, child.end_byte)
+}
@@ -0,0 +1,20 @@
+use tree_sitter::{Node, Range};
+
+use crate::{capture_by_query_source, utils::get_query_source};
+
+pub const DECLARATION: &str = "declaration";
+
+/// Get the names of declarations without a body under the node, e.g. prototypes, from the
+/// `declarations` query.
+pub fn build_declarations(source_code: &Vec<u8>, node: &Node, language_id: &str) -> Vec<Range> {
+    let query_source = match get_query_source(language_id, "declarations") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    capture_by_query_source(source_code, *node, &query_source)
+        .into_iter()
+        .filter(|(capture_name, _)| capture_name == DECLARATION)
+        .map(|(_, node)| node.range())
+        .collect()
+}
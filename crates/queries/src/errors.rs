@@ -1,26 +1,109 @@
 use helper::convert::ts_range_to_lsp_range;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
 use tree_sitter::Node;
 
-use crate::match_by_query_source;
-
 pub const ERROR: &str = "ERROR";
 
-pub fn build_diagnostics(source_code: Vec<u8>, node: &Node) -> Vec<lsp_types::Diagnostic> {
-    let result = match_by_query_source(&source_code, *node, "(ERROR) @ERROR");
+/// Source of the diagnostics of the server
+pub const SOURCE: &str = "tsls";
+
+/// Code of a token the parser expected but didn't find, e.g. a `;`
+pub const MISSING_CODE: &str = "missing-token";
+
+/// Code of tokens the parser couldn't make sense of
+pub const UNEXPECTED_CODE: &str = "unexpected-token";
 
-    let mut errors: Vec<lsp_types::Diagnostic> = Vec::new();
+/// Longest text of an unexpected token quoted in a message
+const MAX_TOKEN_LENGTH: usize = 20;
 
-    for item in &result {
-        for (variable_type, node) in item {
-            if variable_type == ERROR {
-                let range = ts_range_to_lsp_range(&node.range());
+/// Build the syntax errors of the tree: tokens the parser had to insert and the regions it had to
+/// skip. An error region is reported once, whatever errors are nested in it.
+pub fn build_diagnostics(source_code: Vec<u8>, node: &Node) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    collect_errors(&source_code, *node, &mut diagnostics);
+    diagnostics
+}
 
-                let diagnostic = lsp_types::Diagnostic::new_simple(range, "ERROR".to_string());
+fn collect_errors(source_code: &[u8], node: Node, diagnostics: &mut Vec<Diagnostic>) {
+    if !node.has_error() {
+        return;
+    }
 
-                errors.push(diagnostic);
-            }
+    if node.is_missing() {
+        let token = if node.is_named() {
+            humanize(node.kind())
+        } else {
+            format!("`{}`", node.kind())
+        };
+        diagnostics.push(new_diagnostic(
+            &node,
+            format!("missing {}", token),
+            MISSING_CODE,
+        ));
+        return;
+    }
+
+    if node.is_error() {
+        let token = get_first_token(source_code, node);
+        let message = match get_context(node) {
+            Some(context) => format!("unexpected {} in {}", token, context),
+            None => format!("unexpected {}", token),
+        };
+        diagnostics.push(new_diagnostic(&node, message, UNEXPECTED_CODE));
+        return;
+    }
+
+    let mut cursor = node.walk();
+    for child in node.children(&mut cursor) {
+        collect_errors(source_code, child, diagnostics);
+    }
+}
+
+fn new_diagnostic(node: &Node, message: String, code: &str) -> Diagnostic {
+    Diagnostic {
+        range: ts_range_to_lsp_range(&node.range()),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some(SOURCE.to_string()),
+        message,
+        ..Default::default()
+    }
+}
+
+/// Get the first token of an error region, quoted, e.g. `` `}` ``.
+fn get_first_token(source_code: &[u8], node: Node) -> String {
+    let mut token = node;
+    while let Some(child) = token.child(0) {
+        token = child;
+    }
+    let text = String::from_utf8_lossy(&source_code[token.start_byte()..token.end_byte()]);
+    let text = text.lines().next().unwrap_or_default().trim();
+    if text.is_empty() {
+        return "end of input".to_string();
+    }
+    if text.chars().count() > MAX_TOKEN_LENGTH {
+        let text: String = text.chars().take(MAX_TOKEN_LENGTH).collect();
+        format!("`{}…`", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+/// Get what the parser was in when it met the error, e.g. `argument list`. None at the top
+/// level of the file.
+fn get_context(node: Node) -> Option<String> {
+    let mut current = node.parent()?;
+    // the root is the whole file
+    while current.parent().is_some() {
+        if current.is_named() && !current.is_error() {
+            return Some(humanize(current.kind()));
         }
+        current = current.parent()?;
     }
+    None
+}
 
-    errors
+/// Turn a node kind into words, e.g. `argument list` of `argument_list`.
+fn humanize(kind: &str) -> String {
+    kind.trim_start_matches('_').replace('_', " ")
 }
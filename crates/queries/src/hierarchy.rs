@@ -0,0 +1,49 @@
+use tree_sitter::{Node, Range};
+
+use crate::{match_by_query_source, utils::get_query_source};
+
+pub const SUBTYPE: &str = "subtype";
+pub const SUPERTYPE: &str = "supertype";
+
+/// A type extending or implementing another one, by name.
+#[derive(Debug, Clone)]
+pub struct TypeRelation {
+    pub subtype: String,
+    pub subtype_range: Range,
+    pub supertype: String,
+    pub supertype_range: Range,
+}
+
+/// Get every inheritance or implementation under the node, from the `hierarchy` query.
+pub fn build_type_relations(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+) -> Vec<TypeRelation> {
+    let query_source = match get_query_source(language_id, "hierarchy") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut relations: Vec<TypeRelation> = Vec::new();
+    for captures in match_by_query_source(source_code, *node, &query_source) {
+        let subtype = captures.iter().find(|(name, _)| name == SUBTYPE);
+        let supertype = captures.iter().find(|(name, _)| name == SUPERTYPE);
+        if let (Some((_, subtype)), Some((_, supertype))) = (subtype, supertype) {
+            relations.push(TypeRelation {
+                subtype: subtype
+                    .utf8_text(source_code)
+                    .unwrap_or_default()
+                    .to_string(),
+                subtype_range: subtype.range(),
+                supertype: supertype
+                    .utf8_text(source_code)
+                    .unwrap_or_default()
+                    .to_string(),
+                supertype_range: supertype.range(),
+            });
+        }
+    }
+
+    relations
+}
@@ -62,6 +62,8 @@ pub fn update_identifiers_kind(
                 location: node.range(),
                 children: None,
                 belongs_to_scopes,
+                definition_range: None,
+                detail: None,
             };
 
             result.insert((smallest_scope_id, variable_name.to_string()), symbol);
@@ -80,7 +82,7 @@ pub fn update_identifiers_kind(
 }
 
 /// Get completion_kind and symbol_kind
-fn get_kind(capture_name: String) -> (CompletionItemKind, SymbolKind) {
+pub(crate) fn get_kind(capture_name: String) -> (CompletionItemKind, SymbolKind) {
     match capture_name.as_str() {
         "variable" => (CompletionItemKind::VARIABLE, SymbolKind::VARIABLE),
         "function" | "function.macro" => (CompletionItemKind::FUNCTION, SymbolKind::FUNCTION),
@@ -109,6 +111,7 @@ fn get_kind(capture_name: String) -> (CompletionItemKind, SymbolKind) {
         "package" => (CompletionItemKind::MODULE, SymbolKind::MODULE),
         "namespace" => (CompletionItemKind::MODULE, SymbolKind::MODULE),
         "class" => (CompletionItemKind::CLASS, SymbolKind::CLASS),
+        "impl" => (CompletionItemKind::STRUCT, SymbolKind::OBJECT),
         "enum_member" => (CompletionItemKind::ENUM_MEMBER, SymbolKind::ENUM_MEMBER),
         "getter" => (CompletionItemKind::PROPERTY, SymbolKind::PROPERTY),
         "setter" => (CompletionItemKind::PROPERTY, SymbolKind::PROPERTY),
@@ -0,0 +1,43 @@
+use tree_sitter::{Node, Range};
+
+use crate::{match_by_query_source, utils::get_query_source};
+
+pub const OWNER: &str = "implementation.owner";
+pub const METHOD: &str = "implementation.method";
+
+/// A method, abstract or not, and the type it belongs to.
+#[derive(Debug, Clone)]
+pub struct Method {
+    pub owner: String,
+    pub owner_range: Range,
+    pub name: String,
+    pub range: Range,
+}
+
+/// Get every method under the node with its owner, from the `implementations` query.
+pub fn build_methods(source_code: &Vec<u8>, node: &Node, language_id: &str) -> Vec<Method> {
+    let query_source = match get_query_source(language_id, "implementations") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut methods: Vec<Method> = Vec::new();
+    for captures in match_by_query_source(source_code, *node, &query_source) {
+        let owner = captures.iter().find(|(name, _)| name == OWNER);
+        let method = captures.iter().find(|(name, _)| name == METHOD);
+        if let (Some((_, owner)), Some((_, method))) = (owner, method) {
+            methods.push(Method {
+                owner: owner.utf8_text(source_code).unwrap_or_default().to_string(),
+                owner_range: owner.range(),
+                name: method
+                    .utf8_text(source_code)
+                    .unwrap_or_default()
+                    .to_string(),
+                range: method.range(),
+            });
+        }
+    }
+    methods.dedup_by_key(|method| method.range.start_byte);
+
+    methods
+}
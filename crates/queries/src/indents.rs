@@ -1,25 +1,343 @@
-use helper::tree_mutator::get_parser;
-use log::debug;
-use lsp_types::TextEdit;
-use tree_sitter::Tree;
+use std::collections::{HashMap, HashSet};
 
-use crate::{capture_by_query_source, utils::get_query_source};
+use lsp_types::{Position, Range, TextEdit};
+use tree_sitter::{Node, Point, QueryCursor, QueryPredicateArg, Tree};
 
-pub fn text_edits(text: Vec<u8>, language: &str, old_tree: &Tree) -> Result<Vec<TextEdit>, String> {
-    let parser = match get_parser(language.to_string()) {
-        Some(parser) => parser,
-        None => return Err("No parser found for language".to_string()),
-    };
+use crate::utils::get_query_source;
+
+pub const INDENT: &str = "indent";
+pub const BRANCH: &str = "branch";
+pub const INDENT_END: &str = "indent_end";
+pub const ZERO_INDENT: &str = "zero_indent";
+pub const ALIGNED_INDENT: &str = "aligned_indent";
+pub const IGNORE: &str = "ignore";
+pub const AUTO: &str = "auto";
+
+/// Nodes captured by the `indents` query, by node id.
+#[derive(Debug, Default)]
+pub struct IndentCaptures {
+    indent: HashSet<usize>,
+    branch: HashSet<usize>,
+    indent_end: HashSet<usize>,
+    zero_indent: HashSet<usize>,
+    ignore: HashSet<usize>,
+    auto: HashSet<usize>,
+    // the opening delimiter the content is aligned to, e.g. `(` for `()`
+    aligned_indent: HashMap<usize, Option<String>>,
+}
 
-    // we don't need to reparse the tree if the code hasn't changed
-    let query_source = match get_query_source(language, "indents") {
+/// Run the `indents` query on the tree, applying its `#not-has-type?` predicates.
+pub fn build_indent_captures(
+    source_code: &[u8],
+    root_node: &Node,
+    language_id: &str,
+) -> Result<IndentCaptures, String> {
+    let query_source = match get_query_source(language_id, "indents") {
         Some(query_source) => query_source,
         None => return Err("No query source found".to_string()),
     };
+    let query = tree_sitter::Query::new(root_node.language(), &query_source)
+        .map_err(|e| format!("Invalid indents query: {:?}", e))?;
+
+    let mut captures = IndentCaptures::default();
+    let mut query_cursor = QueryCursor::new();
+    for mat in query_cursor.matches(&query, *root_node, source_code) {
+        let is_rejected = query
+            .general_predicates(mat.pattern_index)
+            .iter()
+            .any(|predicate| {
+                let (capture, kinds) = match predicate.args.split_first() {
+                    Some((QueryPredicateArg::Capture(capture), kinds)) => (*capture, kinds),
+                    _ => return false,
+                };
+                let has_type = mat
+                    .captures
+                    .iter()
+                    .filter(|c| c.index == capture)
+                    .any(|c| {
+                        kinds.iter().any(|kind| {
+                            matches!(kind, QueryPredicateArg::String(kind) if **kind == *c.node.kind())
+                        })
+                    });
+                match predicate.operator.as_ref() {
+                    "has-type?" => !has_type,
+                    "not-has-type?" => has_type,
+                    _ => false,
+                }
+            });
+        if is_rejected {
+            continue;
+        }
+
+        let delimiter = query
+            .property_settings(mat.pattern_index)
+            .iter()
+            .find(|property| &*property.key == "delimiter")
+            .and_then(|property| property.value.as_ref().map(|value| value.to_string()));
+
+        for capture in mat.captures {
+            let id = capture.node.id();
+            match query.capture_names()[capture.index as usize].as_str() {
+                INDENT => captures.indent.insert(id),
+                BRANCH => captures.branch.insert(id),
+                INDENT_END => captures.indent_end.insert(id),
+                ZERO_INDENT => captures.zero_indent.insert(id),
+                IGNORE => captures.ignore.insert(id),
+                AUTO => captures.auto.insert(id),
+                ALIGNED_INDENT => {
+                    captures.aligned_indent.insert(id, delimiter.clone());
+                    true
+                }
+                _ => false,
+            };
+        }
+    }
+
+    Ok(captures)
+}
+
+/// Split the source code into lines, as expected by `get_indent` and `get_indent_edit`.
+pub fn split_lines(source_code: &[u8]) -> Vec<&[u8]> {
+    source_code.split(|byte| *byte == b'\n').collect()
+}
+
+/// Compute the indentation of the row in columns, a tab spanning up to `tab_size` of them.
+/// Return None when the row should keep its indentation, e.g. inside a comment or a string.
+/// `shifts` are the columns added to the rows which were re-indented already, the rows aligned
+/// to them follow.
+pub fn get_indent(
+    captures: &IndentCaptures,
+    source_code: &[u8],
+    lines: &[&[u8]],
+    root_node: &Node,
+    row: usize,
+    tab_size: usize,
+    shifts: &HashMap<usize, i64>,
+) -> Option<usize> {
+    let line = lines.get(row).copied().unwrap_or_default();
+
+    let mut node = if is_blank(line) {
+        // an empty line is indented like the content following the previous line
+        let previous_row = match (0..row).rev().find(|row| !is_blank(lines[*row])) {
+            Some(previous_row) => previous_row,
+            None => return Some(0),
+        };
+        let previous_line = lines[previous_row];
+        let first_column = get_first_column(previous_line);
+        let mut last_column = get_last_column(previous_line);
+        let mut node = get_node_at(root_node, previous_row, last_column);
+
+        // a trailing comment says nothing about the code
+        if node.kind().contains("comment") {
+            let first_node = get_node_at(root_node, previous_row, first_column);
+            if first_node.id() != node.id() {
+                last_column = get_last_column(&previous_line[..node.start_position().column]);
+                node = get_node_at(root_node, previous_row, last_column);
+            }
+        }
+        if captures.indent_end.contains(&node.id()) {
+            node = get_node_at(root_node, row, 0);
+        }
+        node
+    } else {
+        get_node_at(root_node, row, get_first_column(line))
+    };
+
+    if captures.zero_indent.contains(&node.id()) {
+        return Some(0);
+    }
+
+    let mut indent: i64 = 0;
+    let indent_size = tab_size as i64;
+    let mut processed_rows: HashSet<usize> = HashSet::new();
+    loop {
+        let id = node.id();
+        let start_row = node.start_position().row;
+        let end_row = node.end_position().row;
+        let is_inside = start_row < row && row <= end_row;
+
+        if !captures.indent.contains(&id)
+            && (captures.auto.contains(&id) || captures.ignore.contains(&id))
+            && is_inside
+        {
+            return None;
+        }
+
+        let mut is_processed = false;
+        if !processed_rows.contains(&start_row) {
+            if captures.branch.contains(&id) && start_row == row {
+                indent -= indent_size;
+                is_processed = true;
+            }
+            // nodes on a single line or starting on the row don't indent it
+            if captures.indent.contains(&id) && start_row != end_row && start_row != row {
+                indent += indent_size;
+                is_processed = true;
+            }
+        }
+
+        if let Some(delimiter) = captures.aligned_indent.get(&id) {
+            if start_row != end_row && start_row != row {
+                let opening_delimiter = delimiter
+                    .as_ref()
+                    .and_then(|delimiter| delimiter.chars().next());
+                match find_opening_delimiter(&node, opening_delimiter, source_code) {
+                    // `f(` followed by a new line
+                    Some((_, true)) => indent += indent_size,
+                    Some((delimiter_node, false)) => {
+                        let position = delimiter_node.start_position();
+                        let delimiter_line = lines.get(position.row).copied().unwrap_or_default();
+                        let column = get_visual_column(delimiter_line, position.column, tab_size)
+                            as i64
+                            + shifts.get(&position.row).copied().unwrap_or_default();
+                        return Some((indent.max(0) + column.max(0) + 1) as usize);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if is_processed {
+            processed_rows.insert(start_row);
+        }
+        node = match node.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+
+    Some(indent.max(0) as usize)
+}
+
+/// Replace the leading whitespace of the row, when it differs from the indentation.
+pub fn get_indent_edit(
+    lines: &[&[u8]],
+    row: usize,
+    indent: usize,
+    tab_size: usize,
+    insert_spaces: bool,
+) -> Option<TextEdit> {
+    let line = lines.get(row)?;
+    let current = &line[..get_first_column(line)];
+
+    let expected = if insert_spaces || tab_size == 0 {
+        " ".repeat(indent)
+    } else {
+        format!(
+            "{}{}",
+            "\t".repeat(indent / tab_size),
+            " ".repeat(indent % tab_size)
+        )
+    };
+    if current == expected.as_bytes() {
+        return None;
+    }
+
+    Some(TextEdit {
+        range: Range {
+            start: Position::new(row as u32, 0),
+            // whitespace is ascii, bytes are utf-16 code units too
+            end: Position::new(row as u32, current.len() as u32),
+        },
+        new_text: expected,
+    })
+}
+
+/// Find the opening delimiter among the children of the node, and whether it ends its line.
+fn find_opening_delimiter<'a>(
+    node: &Node<'a>,
+    delimiter: Option<char>,
+    source_code: &[u8],
+) -> Option<(Node<'a>, bool)> {
+    let delimiter = match delimiter {
+        Some(delimiter) => delimiter.to_string(),
+        None => return Some((*node, false)),
+    };
+    let mut cursor = node.walk();
+    let delimiter_node = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == delimiter)?;
+
+    let rest = &source_code[delimiter_node.end_byte()..];
+    let rest = &rest[..rest
+        .iter()
+        .position(|byte| *byte == b'\n')
+        .unwrap_or(rest.len())];
+    Some((delimiter_node, is_blank(rest)))
+}
+
+fn get_node_at<'a>(root_node: &Node<'a>, row: usize, column: usize) -> Node<'a> {
+    let point = Point::new(row, column);
+    root_node
+        .descendant_for_point_range(point, point)
+        .unwrap_or(*root_node)
+}
+
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(|byte| byte.is_ascii_whitespace())
+}
+
+/// The column of the first character which is not a whitespace.
+fn get_first_column(line: &[u8]) -> usize {
+    line.iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(line.len())
+}
+
+/// The column the byte column of the line is displayed at, a tab advancing to the next multiple
+/// of `tab_size` and a multibyte character taking one column.
+fn get_visual_column(line: &[u8], column: usize, tab_size: usize) -> usize {
+    line[..column.min(line.len())]
+        .iter()
+        .fold(0, |visual_column, byte| match byte {
+            b'\t' if tab_size > 0 => (visual_column / tab_size + 1) * tab_size,
+            // continuation bytes of a multibyte character
+            0x80..=0xbf => visual_column,
+            _ => visual_column + 1,
+        })
+}
+
+/// The column of the last character which is not a whitespace.
+fn get_last_column(line: &[u8]) -> usize {
+    line.iter()
+        .rposition(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(0)
+}
+
+/// Re-indent the rows of the document, lines inside `@ignore` nodes are left untouched.
+pub fn text_edits(
+    text: &[u8],
+    language: &str,
+    tree: &Tree,
+    rows: std::ops::Range<usize>,
+    tab_size: usize,
+    insert_spaces: bool,
+) -> Result<Vec<TextEdit>, String> {
+    let root_node = tree.root_node();
+    let captures = build_indent_captures(text, &root_node, language)?;
 
-    for (capture, node) in capture_by_query_source(&text, old_tree.root_node(), &query_source) {
-        debug!("capture: {:?}, {:?}", capture, node);
+    let lines = split_lines(text);
+    let mut shifts: HashMap<usize, i64> = HashMap::new();
+    let mut text_edits: Vec<TextEdit> = Vec::new();
+    for row in rows {
+        let line = match lines.get(row) {
+            Some(line) => line,
+            None => break,
+        };
+        // blank lines are not filled with whitespace
+        if is_blank(line) {
+            continue;
+        }
+        let indent = match get_indent(&captures, text, &lines, &root_node, row, tab_size, &shifts) {
+            Some(indent) => indent,
+            None => continue,
+        };
+        if let Some(text_edit) = get_indent_edit(&lines, row, indent, tab_size, insert_spaces) {
+            let old_indent = get_visual_column(line, get_first_column(line), tab_size);
+            shifts.insert(row, indent as i64 - old_indent as i64);
+            text_edits.push(text_edit);
+        }
     }
 
-    Ok(vec![])
+    Ok(text_edits)
 }
@@ -1,7 +1,15 @@
 #![feature(is_some_with)]
+pub mod calls;
+pub mod children;
+pub mod declarations;
 pub mod errors;
+pub mod hierarchy;
 pub mod highlight;
+pub mod implementations;
 pub mod indents;
+pub mod linked;
+pub mod links;
+pub mod lints;
 pub mod locals;
 pub mod utils;
 
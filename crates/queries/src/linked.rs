@@ -0,0 +1,42 @@
+use tree_sitter::{Node, Range};
+
+use crate::{match_by_query_source, utils::get_query_source};
+
+pub const LINKED: &str = "linked";
+
+/// Get the groups of ranges which must be edited together, from the `linked` query.
+/// A group is only kept while all of its ranges hold the same text.
+pub fn build_linked_ranges(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+) -> Vec<Vec<Range>> {
+    let query_source = match get_query_source(language_id, "linked") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut groups: Vec<Vec<Range>> = Vec::new();
+    for captures in match_by_query_source(source_code, *node, &query_source) {
+        let nodes: Vec<Node> = captures
+            .into_iter()
+            .filter(|(capture_name, _)| capture_name == LINKED)
+            .map(|(_, node)| node)
+            .collect();
+        if nodes.len() < 2 {
+            continue;
+        }
+
+        // e.g. `<div></span>` is a typo, not a pair
+        let text = nodes[0].utf8_text(source_code).unwrap_or_default();
+        if nodes
+            .iter()
+            .any(|node| node.utf8_text(source_code).unwrap_or_default() != text)
+        {
+            continue;
+        }
+        groups.push(nodes.iter().map(|node| node.range()).collect());
+    }
+
+    groups
+}
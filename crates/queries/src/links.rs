@@ -0,0 +1,57 @@
+use tree_sitter::{Node, Range};
+
+use crate::{capture_by_query_source, utils::get_query_source};
+
+pub const LINK: &str = "link";
+
+/// Delimiters of string literals, they are not part of the path
+const DELIMITERS: [char; 5] = ['"', '\'', '`', '<', '>'];
+
+/// A path, module or package named by the document, e.g. `#include "a.h"`.
+#[derive(Debug, Clone)]
+pub struct Link {
+    /// The capture name without the `link.` prefix: `path`, `system`, `module` or `package`
+    pub kind: String,
+    pub target: String,
+    pub range: Range,
+}
+
+/// Get every link under the node, from the `links` query. The target of a link being typed may
+/// be empty, e.g. `#include ""`.
+pub fn build_links(source_code: &Vec<u8>, node: &Node, language_id: &str) -> Vec<Link> {
+    let query_source = match get_query_source(language_id, "links") {
+        Some(query_source) => query_source,
+        None => return vec![],
+    };
+
+    let mut links: Vec<Link> = Vec::new();
+    for (capture_name, node) in capture_by_query_source(source_code, *node, &query_source) {
+        let kind = match capture_name.strip_prefix(LINK) {
+            Some(kind) if kind.starts_with('.') => kind.trim_start_matches('.'),
+            _ => continue,
+        };
+
+        let text = node.utf8_text(source_code).unwrap_or_default();
+        let mut range = node.range();
+        let target = if text.len() >= 2
+            && text.starts_with(DELIMITERS)
+            && text.ends_with(DELIMITERS)
+            && node.start_position().row == node.end_position().row
+        {
+            range.start_byte += 1;
+            range.start_point.column += 1;
+            range.end_byte -= 1;
+            range.end_point.column -= 1;
+            &text[1..text.len() - 1]
+        } else {
+            text
+        };
+        links.push(Link {
+            kind: kind.to_string(),
+            target: target.to_string(),
+            range,
+        });
+    }
+
+    links
+}
@@ -0,0 +1,93 @@
+use helper::convert::ts_range_to_lsp_range;
+use log::error;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString};
+use tree_sitter::{Node, Query, QueryCursor};
+
+use crate::{errors::SOURCE, utils::get_query_source};
+
+pub const DIAGNOSTIC: &str = "diagnostic";
+
+/// Message of a rule without one
+const DEFAULT_MESSAGE: &str = "lint";
+
+/// Evaluate the rules of the `lints` query of the language.
+///
+/// Each pattern captures the offending node as `@diagnostic` and describes it with
+/// `(#set! message "...")`, `(#set! severity "...")` and `(#set! code "...")`, severity being one
+/// of `error`, `warning`, `information` and `hint`, `warning` by default. Only the rules whose
+/// code is in `rules` are evaluated, all of them if it's empty.
+pub fn build_lint_diagnostics(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+    rules: &[String],
+) -> Vec<Diagnostic> {
+    match get_query_source(language_id, "lints") {
+        Some(query_source) => {
+            build_lint_diagnostics_by_query_source(source_code, node, &query_source, rules)
+        }
+        None => vec![],
+    }
+}
+
+/// Evaluate the rules of a `lints` query, see `build_lint_diagnostics`.
+pub fn build_lint_diagnostics_by_query_source(
+    source_code: &Vec<u8>,
+    node: &Node,
+    query_source: &str,
+    rules: &[String],
+) -> Vec<Diagnostic> {
+    // written by users, it must not bring the server down
+    let query = match Query::new(node.language(), query_source) {
+        Ok(query) => query,
+        Err(err) => {
+            error!("invalid lints: {:?}", err);
+            return vec![];
+        }
+    };
+    let diagnostic_index = match query.capture_index_for_name(DIAGNOSTIC) {
+        Some(index) => index,
+        None => return vec![],
+    };
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    let mut query_cursor = QueryCursor::new();
+    for m in query_cursor.matches(&query, *node, &**source_code) {
+        let property = |key: &str| {
+            query
+                .property_settings(m.pattern_index)
+                .iter()
+                .find(|property| &*property.key == key)
+                .and_then(|property| property.value.as_deref())
+        };
+        let code = property("code");
+        if !rules.is_empty() && !code.is_some_and(|code| rules.iter().any(|rule| rule == code)) {
+            continue;
+        }
+
+        for capture in m.captures {
+            if capture.index != diagnostic_index {
+                continue;
+            }
+            diagnostics.push(Diagnostic {
+                range: ts_range_to_lsp_range(&capture.node.range()),
+                severity: Some(get_severity(property("severity"))),
+                code: code.map(|code| NumberOrString::String(code.to_string())),
+                source: Some(SOURCE.to_string()),
+                message: property("message").unwrap_or(DEFAULT_MESSAGE).to_string(),
+                ..Default::default()
+            });
+        }
+    }
+
+    diagnostics
+}
+
+fn get_severity(severity: Option<&str>) -> DiagnosticSeverity {
+    match severity {
+        Some("error") => DiagnosticSeverity::ERROR,
+        Some("information" | "info") => DiagnosticSeverity::INFORMATION,
+        Some("hint") => DiagnosticSeverity::HINT,
+        _ => DiagnosticSeverity::WARNING,
+    }
+}
@@ -1,21 +1,71 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use helper::types::Symbol;
 use log::error;
 use lsp_types::{CompletionItemKind, SymbolKind};
-use tree_sitter::Node;
+use tree_sitter::{Node, Range};
 
 use crate::{
     capture_by_query_source, match_by_query_source,
-    utils::{get_query_source, get_smallest_scope_id_by_node},
+    utils::{get_query_source, get_scope_chain_by_node, get_scope_chain_by_offset},
 };
 
 // TODO: Hard-coded for now.
 pub const REFERENCE: &str = "reference";
+pub const REFERENCE_WRITE: &str = "reference.write";
+pub const DIFINITION: &str = "definition";
 pub const DIFINITION_VAR: &str = "definition.var";
 pub const DIFINITION_FUNCTION: &str = "definition.function";
+pub const DIFINITION_PARAMETER: &str = "definition.parameter";
+pub const DIFINITION_IMPORT: &str = "definition.import";
 pub const SCOPE: &str = "scope";
 
+/// Definitions which are visible in the scope enclosing the one they open,
+/// e.g. the name of a function is not local to the function itself.
+const PARENT_SCOPED_DIFINITIONS: [&str; 5] = [
+    "definition.function",
+    "definition.method",
+    "definition.type",
+    "definition.macro",
+    "definition.namespace",
+];
+
+/// Languages whose local bindings can't be used before they are declared, their block-scoped
+/// bindings are not visible from above their definitions, with the declarations which are
+/// hoisted nonetheless. Python is left out, a name bound anywhere in a function is local to the
+/// whole function and a loop may use it before the assignment.
+const DECLARED_BEFORE_USE: [(&str, &[&str]); 10] = [
+    ("c", &[]),
+    ("cpp", &[]),
+    ("cuda", &[]),
+    ("glsl", &[]),
+    ("rust", &[]),
+    ("go", &[]),
+    ("kotlin", &[]),
+    ("hare", &[]),
+    // `let`, `const` and classes are in their temporal dead zone until declared
+    ("javascript", &ECMA_HOISTED),
+    ("typescript", &ECMA_HOISTED),
+];
+
+/// `var` and functions are visible in the whole scope they are declared in.
+const ECMA_HOISTED: [&str; 3] = [
+    "variable_declaration",
+    "function_declaration",
+    "generator_function_declaration",
+];
+
+/// Definitions which are visible outside of the file even when they are not top-level,
+/// e.g. methods of a class.
+const MEMBER_DIFINITIONS: [&str; 6] = [
+    "definition.function",
+    "definition.method",
+    "definition.type",
+    "definition.macro",
+    "definition.namespace",
+    "definition.field",
+];
+
 /// build scopes from biggest to smallest
 fn build_scopes(
     source_code: &Vec<u8>,
@@ -67,16 +117,16 @@ fn build_definitions_and_identifiers(
     };
     let result = capture_by_query_source(source_code, node.to_owned(), query_source.as_str());
     let mut definitions: HashMap<String, Vec<Symbol>> = HashMap::new();
+    // a node can be captured by more than one reference pattern, e.g. calls
+    let mut visited_references: HashSet<usize> = HashSet::new();
 
-    // use name + smallest_scope_id as key
+    // use name + scope_id as key
     for (variable_type, node) in result {
-        // for (variable_type, node) in item {
-
         let variable_name = node.utf8_text(source_code).unwrap();
         match variable_type.as_str() {
-            DIFINITION_VAR | DIFINITION_FUNCTION => {
-                let smallest_scope_id = get_smallest_scope_id_by_node(&node, scopes);
-                let key = format!("{}:{}", variable_name, smallest_scope_id);
+            definition if definition.starts_with(DIFINITION) => {
+                let scope_id = get_binding_scope_id(definition, &node, scopes);
+                let key = format!("{}:{}", variable_name, scope_id);
 
                 // check if the key already exists
                 if definitions.contains_key(&key) {
@@ -86,9 +136,11 @@ fn build_definitions_and_identifiers(
                 definitions.insert(key, vec![]);
             }
             REFERENCE => {
-                let smallest_scope_id = get_smallest_scope_id_by_node(&node, scopes);
-
-                let key = format!("{}:{}", variable_name, smallest_scope_id);
+                if !visited_references.insert(node.start_byte()) {
+                    continue;
+                }
+                let scope_chain = get_scope_chain_by_node(&node, scopes);
+                let smallest_scope_id = scope_chain[0];
 
                 let belongs_to_scopes = scopes[0..smallest_scope_id].to_owned();
                 let symbol = Symbol {
@@ -98,16 +150,23 @@ fn build_definitions_and_identifiers(
                     location: node.range(),
                     children: None,
                     belongs_to_scopes,
+                    definition_range: None,
+                    detail: None,
                 };
 
-                let vector = match definitions.get_mut(&key) {
-                    Some(v) => v,
+                // the innermost visible definition wins
+                let key = match scope_chain
+                    .iter()
+                    .map(|id| format!("{}:{}", variable_name, id))
+                    .find(|key| definitions.contains_key(key))
+                {
+                    Some(key) => key,
                     None => {
                         // we don't have that variable, just ignore it
                         continue;
                     }
                 };
-                vector.push(symbol.clone());
+                definitions.get_mut(&key).unwrap().push(symbol);
             }
             _ => {
                 // TODO: check children
@@ -116,16 +175,6 @@ fn build_definitions_and_identifiers(
         }
     }
 
-    // TODO: query struct/class fields, add it to children
-    let query_source = {
-        if let Some(x) = get_query_source(language_id, "children") {
-            x
-        } else {
-            "".to_string()
-        }
-    };
-    let result = capture_by_query_source(source_code, node.to_owned(), query_source.as_str());
-
     definitions
 }
 
@@ -146,3 +195,440 @@ pub fn build_definitions_and_scopes(
 
     (definitions, scopes, identifiers)
 }
+
+/// Get the scope of the binding the node refers to. A binding of an inner scope which is only
+/// declared after the node doesn't capture it, the node is one of the occurrences of the binding
+/// it was attached to.
+fn find_binding_scope_id(
+    definitions_lookup_map: &HashMap<String, Vec<Symbol>>,
+    variable_name: &str,
+    node: &Node,
+    scopes: &[Range],
+) -> Option<usize> {
+    let scope_ids: Vec<usize> = get_scope_chain_by_node(node, scopes)
+        .into_iter()
+        .filter(|id| definitions_lookup_map.contains_key(&format!("{}:{}", variable_name, id)))
+        .collect();
+    scope_ids
+        .iter()
+        .find(|id| {
+            definitions_lookup_map[&format!("{}:{}", variable_name, id)]
+                .iter()
+                .any(|symbol| symbol.location.start_byte == node.start_byte())
+        })
+        .or_else(|| scope_ids.first())
+        .copied()
+}
+
+/// Find the occurrences of the binding the node refers to.
+/// The first occurrence is the definition itself.
+pub fn find_definition<'a>(
+    definitions_lookup_map: &'a HashMap<String, Vec<Symbol>>,
+    variable_name: &str,
+    node: &Node,
+    scopes: &[Range],
+) -> Option<&'a Vec<Symbol>> {
+    find_binding_scope_id(definitions_lookup_map, variable_name, node, scopes)
+        .and_then(|id| definitions_lookup_map.get(&format!("{}:{}", variable_name, id)))
+}
+
+/// Find the occurrences of the binding of the name visible at the offset, e.g. where a word is
+/// being typed. The first occurrence is the definition itself.
+pub fn find_definition_by_offset<'a>(
+    definitions_lookup_map: &'a HashMap<String, Vec<Symbol>>,
+    variable_name: &str,
+    offset: usize,
+    scopes: &[Range],
+) -> Option<&'a Vec<Symbol>> {
+    get_scope_chain_by_offset(offset, scopes)
+        .iter()
+        .find_map(|id| definitions_lookup_map.get(&format!("{}:{}", variable_name, id)))
+}
+
+/// Get the names of the bindings visible at the offset and how deep their scopes are, the file
+/// scope being 0. Of bindings with the same name only the innermost is visible. The binding
+/// being declared at the offset is left out, so are block-scoped bindings declared after it
+/// where the language requires declaration before use.
+pub fn find_visible_definitions<'a>(
+    definitions_lookup_map: &'a HashMap<String, Vec<Symbol>>,
+    offset: usize,
+    scopes: &[Range],
+    root_node: &Node,
+    language_id: &str,
+) -> Vec<(&'a str, usize)> {
+    let mut bindings_by_scope: HashMap<usize, Vec<(&str, Option<&Symbol>)>> = HashMap::new();
+    for (key, occurrences) in definitions_lookup_map {
+        if let Some((name, scope_id)) = key
+            .rsplit_once(':')
+            .and_then(|(name, scope_id)| Some((name, scope_id.parse::<usize>().ok()?)))
+        {
+            bindings_by_scope
+                .entry(scope_id)
+                .or_default()
+                .push((name, occurrences.first()));
+        }
+    }
+
+    let hoisted = DECLARED_BEFORE_USE
+        .iter()
+        .find(|(language, _)| *language == language_id)
+        .map(|(_, hoisted)| *hoisted);
+    let chain = get_scope_chain_by_offset(offset, scopes);
+    let mut seen: HashSet<&str> = HashSet::new();
+    let mut visible: Vec<(&str, usize)> = Vec::new();
+    for (index, scope_id) in chain.iter().enumerate() {
+        let is_top_level = is_top_level_scope(*scope_id, scopes, root_node);
+        for (name, definition) in bindings_by_scope.get(scope_id).into_iter().flatten() {
+            let start_byte = definition.map(|definition| definition.location.start_byte);
+            let is_declared_later = match (hoisted, start_byte) {
+                (Some(hoisted), Some(start_byte)) => {
+                    start_byte > offset && !is_hoisted(start_byte, scopes, root_node, hoisted)
+                }
+                _ => false,
+            };
+            if start_byte == Some(offset) || (!is_top_level && is_declared_later) {
+                continue;
+            }
+            if seen.insert(name) {
+                visible.push((name, chain.len() - 1 - index));
+            }
+        }
+    }
+    visible
+}
+
+/// Whether the binding defined at the byte is declared by one of the hoisted declarations, which
+/// make it visible from anywhere in its scope.
+fn is_hoisted(start_byte: usize, scopes: &[Range], root_node: &Node, hoisted: &[&str]) -> bool {
+    let mut node = root_node.named_descendant_for_byte_range(start_byte, start_byte);
+    while let Some(current) = node {
+        if hoisted.contains(&current.kind()) {
+            return true;
+        }
+        // the declarations of an enclosing scope don't declare the binding
+        if scopes.contains(&current.range()) {
+            return false;
+        }
+        node = current.parent();
+    }
+    false
+}
+
+/// Whether the node refers to a binding of the file scope, or to no binding of the file at all,
+/// e.g. a function defined in another file.
+pub fn is_top_level_reference(
+    definitions_lookup_map: &HashMap<String, Vec<Symbol>>,
+    variable_name: &str,
+    node: &Node,
+    scopes: &[Range],
+    root_node: &Node,
+) -> bool {
+    match find_binding_scope_id(definitions_lookup_map, variable_name, node, scopes) {
+        Some(scope_id) => is_top_level_scope(scope_id, scopes, root_node),
+        None => true,
+    }
+}
+
+/// Get ranges of the occurrences of a top-level binding in a file, its top-level definitions and
+/// the references which are not shadowed by a local binding.
+pub fn build_top_level_occurrences(
+    source_code: &Vec<u8>,
+    root_node: &Node,
+    language_id: &str,
+    variable_name: &str,
+) -> (Vec<Range>, Vec<Range>) {
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+    let (definitions_lookup_map, scopes, _) =
+        build_definitions_and_scopes(source_code, root_node, language_id);
+
+    let mut definitions: Vec<Range> = Vec::new();
+    let mut references: Vec<Range> = Vec::new();
+    for (capture_name, node) in
+        capture_by_query_source(source_code, root_node.to_owned(), query_source.as_str())
+    {
+        if node.utf8_text(source_code) != Ok(variable_name) {
+            continue;
+        }
+        if capture_name.starts_with(DIFINITION) {
+            let scope_id = get_binding_scope_id(&capture_name, &node, &scopes);
+            if is_top_level_scope(scope_id, &scopes, root_node) {
+                definitions.push(node.range());
+            }
+        } else if capture_name == REFERENCE
+            && is_top_level_reference(
+                &definitions_lookup_map,
+                variable_name,
+                &node,
+                &scopes,
+                root_node,
+            )
+        {
+            references.push(node.range());
+        }
+    }
+
+    // a node can be captured by more than one pattern, and definitions are references too
+    definitions.sort_by_key(|range| range.start_byte);
+    definitions.dedup_by_key(|range| range.start_byte);
+    references.sort_by_key(|range| range.start_byte);
+    references.dedup_by_key(|range| range.start_byte);
+    references.retain(|reference| {
+        !definitions
+            .iter()
+            .any(|definition| definition.start_byte == reference.start_byte)
+    });
+
+    (definitions, references)
+}
+
+/// Find what renaming the occurrences of a binding to the new name would conflict with:
+/// bindings of the new name which are visible from an occurrence, and references to the new name
+/// which a renamed definition would capture.
+pub fn find_rename_conflicts(
+    source_code: &Vec<u8>,
+    root_node: &Node,
+    language_id: &str,
+    occurrences: &[Range],
+    new_name: &str,
+) -> Vec<Range> {
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+    let (scopes, _) = build_scopes(source_code, root_node.to_owned(), language_id);
+    let captures =
+        capture_by_query_source(source_code, root_node.to_owned(), query_source.as_str());
+
+    // the range a definition is visible in, the whole file for top-level ones
+    let get_visible_range = |capture_name: &str, node: &Node| -> (usize, usize) {
+        match scopes.get(get_binding_scope_id(capture_name, node, &scopes)) {
+            Some(scope) => (scope.start_byte, scope.end_byte),
+            None => (0, usize::MAX),
+        }
+    };
+    let is_occurrence = |node: &Node| {
+        occurrences
+            .iter()
+            .any(|occurrence| occurrence.start_byte == node.start_byte())
+    };
+    let renamed_definitions: Vec<(usize, usize)> = captures
+        .iter()
+        .filter(|(capture_name, node)| capture_name.starts_with(DIFINITION) && is_occurrence(node))
+        .map(|(capture_name, node)| get_visible_range(capture_name, node))
+        .collect();
+
+    let new_definitions: Vec<(&Node, (usize, usize))> = captures
+        .iter()
+        .filter(|(capture_name, node)| {
+            capture_name.starts_with(DIFINITION) && node.utf8_text(source_code) == Ok(new_name)
+        })
+        .map(|(capture_name, node)| (node, get_visible_range(capture_name, node)))
+        .collect();
+
+    let mut conflicts: Vec<Range> = Vec::new();
+    for (node, (start_byte, end_byte)) in new_definitions.iter() {
+        if occurrences.iter().any(|occurrence| {
+            *start_byte <= occurrence.start_byte && occurrence.end_byte <= *end_byte
+        }) {
+            conflicts.push(node.range());
+        }
+    }
+    for (capture_name, node) in captures.iter() {
+        if capture_name != REFERENCE
+            || node.utf8_text(source_code) != Ok(new_name)
+            || new_definitions
+                .iter()
+                .any(|(definition, _)| definition.start_byte() == node.start_byte())
+        {
+            continue;
+        }
+        let contains = |(start_byte, end_byte): &(usize, usize)| {
+            *start_byte <= node.start_byte() && node.end_byte() <= *end_byte
+        };
+        // the reference stays bound to a binding of the new name between it and the definition
+        let is_captured = renamed_definitions.iter().any(|renamed| {
+            contains(renamed)
+                && !new_definitions.iter().any(|(_, visible)| {
+                    contains(visible) && renamed.0 <= visible.0 && visible.1 <= renamed.1
+                })
+        });
+        if is_captured {
+            conflicts.push(node.range());
+        }
+    }
+
+    conflicts.sort_by_key(|range| range.start_byte);
+    conflicts.dedup_by_key(|range| range.start_byte);
+    conflicts
+}
+
+/// Get ranges of all nodes that write to a binding, definitions and assignment targets.
+pub fn build_write_ranges(source_code: &Vec<u8>, node: &Node, language_id: &str) -> Vec<Range> {
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+
+    capture_by_query_source(source_code, node.to_owned(), query_source.as_str())
+        .into_iter()
+        .filter(|(capture_name, _)| {
+            capture_name.starts_with(DIFINITION) || capture_name == REFERENCE_WRITE
+        })
+        .map(|(_, node)| node.range())
+        .collect()
+}
+
+/// Get ranges of the names of all definitions.
+pub fn build_definition_ranges(
+    source_code: &Vec<u8>,
+    node: &Node,
+    language_id: &str,
+) -> Vec<Range> {
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+
+    capture_by_query_source(source_code, node.to_owned(), query_source.as_str())
+        .into_iter()
+        .filter(|(capture_name, _)| capture_name.starts_with(DIFINITION))
+        .map(|(_, node)| node.range())
+        .collect()
+}
+
+/// Get the id of the scope a definition is visible in.
+fn get_binding_scope_id(definition: &str, node: &Node, scopes: &[Range]) -> usize {
+    let scope_chain = get_scope_chain_by_node(node, scopes);
+    if PARENT_SCOPED_DIFINITIONS.contains(&definition) && scope_chain.len() > 1 {
+        scope_chain[1]
+    } else {
+        scope_chain[0]
+    }
+}
+
+/// Whether the scope is the whole file, either the root node or no scope at all.
+fn is_top_level_scope(scope_id: usize, scopes: &[Range], root_node: &Node) -> bool {
+    match scopes.get(scope_id) {
+        Some(scope) => {
+            scope.start_byte <= root_node.start_byte() && root_node.end_byte() <= scope.end_byte
+        }
+        None => true,
+    }
+}
+
+/// Get completion_kind and symbol_kind of a definition capture.
+fn get_definition_kind(definition: &str) -> (CompletionItemKind, SymbolKind) {
+    match definition
+        .trim_start_matches(DIFINITION)
+        .trim_start_matches('.')
+    {
+        "function" => (CompletionItemKind::FUNCTION, SymbolKind::FUNCTION),
+        "method" => (CompletionItemKind::METHOD, SymbolKind::METHOD),
+        "type" => (CompletionItemKind::CLASS, SymbolKind::CLASS),
+        "macro" => (CompletionItemKind::FUNCTION, SymbolKind::FUNCTION),
+        "namespace" => (CompletionItemKind::MODULE, SymbolKind::NAMESPACE),
+        "import" => (CompletionItemKind::MODULE, SymbolKind::MODULE),
+        "field" => (CompletionItemKind::FIELD, SymbolKind::FIELD),
+        _ => (CompletionItemKind::VARIABLE, SymbolKind::VARIABLE),
+    }
+}
+
+/// Build symbols of the definitions that are visible from other files:
+/// top-level bindings, functions, types and members, but no locals or parameters.
+pub fn build_definition_symbols(
+    source_code: &Vec<u8>,
+    root_node: &Node,
+    language_id: &str,
+) -> Vec<Symbol> {
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+    let (scopes, _) = build_scopes(source_code, root_node.to_owned(), language_id);
+
+    let mut symbols: Vec<Symbol> = Vec::new();
+    // a node can be captured by more than one definition pattern
+    let mut visited: HashSet<usize> = HashSet::new();
+
+    for (capture_name, node) in
+        capture_by_query_source(source_code, root_node.to_owned(), query_source.as_str())
+    {
+        if !capture_name.starts_with(DIFINITION)
+            || !visited.insert(node.start_byte())
+            || capture_name == DIFINITION_PARAMETER
+            || capture_name == DIFINITION_IMPORT
+        {
+            continue;
+        }
+
+        let scope_id = get_binding_scope_id(&capture_name, &node, &scopes);
+        if !is_top_level_scope(scope_id, &scopes, root_node)
+            && !MEMBER_DIFINITIONS.contains(&capture_name.as_str())
+        {
+            continue;
+        }
+
+        let (completion_kind, symbol_kind) = get_definition_kind(&capture_name);
+        symbols.push(Symbol {
+            name: node.utf8_text(source_code).unwrap_or_default().to_string(),
+            completion_kind: vec![completion_kind],
+            symbol_kind: vec![symbol_kind],
+            location: node.range(),
+            children: None,
+            belongs_to_scopes: vec![],
+            definition_range: None,
+            detail: None,
+        });
+    }
+
+    symbols
+}
+
+/// Get the parameter list of the function the name node defines,
+/// e.g. `(int a, int b)` for the `add` of `int add(int a, int b) { ... }`.
+pub fn find_parameter_list<'a>(name_node: &Node<'a>) -> Option<Node<'a>> {
+    let mut current = *name_node;
+    // the name is nested in a declarator in c, and functions can be assigned in javascript
+    for _ in 0..3 {
+        if let Some(parameters) = current.child_by_field_name("parameters") {
+            return Some(parameters);
+        }
+        if let Some(parameters) = current
+            .child_by_field_name("value")
+            .and_then(|value| value.child_by_field_name("parameters"))
+        {
+            return Some(parameters);
+        }
+        current = current.parent()?;
+    }
+    None
+}
+
+/// Get the names of the parameters of the function the name node defines, in order.
+/// Return None if the node is not the name of a function.
+pub fn get_parameter_names(
+    source_code: &Vec<u8>,
+    name_node: &Node,
+    language_id: &str,
+) -> Option<Vec<String>> {
+    let parameter_list = find_parameter_list(name_node)?;
+    let query_source = get_query_source(language_id, "locals").unwrap_or_default();
+
+    let mut parameters: Vec<Node> =
+        capture_by_query_source(source_code, parameter_list, query_source.as_str())
+            .into_iter()
+            .filter(|(capture_name, _)| capture_name == DIFINITION_PARAMETER)
+            .map(|(_, node)| node)
+            .collect();
+    // default values may contain closures with parameters of their own
+    parameters.retain(|node| {
+        let mut ancestor = node.parent();
+        while let Some(current) = ancestor {
+            if current.id() == parameter_list.id() {
+                return true;
+            }
+            if current.child_by_field_name("parameters").is_some() {
+                return false;
+            }
+            ancestor = current.parent();
+        }
+        false
+    });
+    parameters.sort_by_key(|node| node.start_byte());
+    parameters.dedup_by_key(|node| node.start_byte());
+
+    Some(
+        parameters
+            .iter()
+            .map(|node| node.utf8_text(source_code).unwrap_or_default().to_string())
+            .collect(),
+    )
+}
@@ -1,4 +1,4 @@
-pub use embed::get_query_source;
+pub use embed::{get_query_source, get_snippets_source};
 use helper::convert::ts_point_to_lsp_position;
 use lsp_types::Position;
 use tree_sitter::{Node, Range};
@@ -35,7 +35,48 @@ pub fn get_smallest_scope_id_by_node(node: &Node, scopes: &[Range]) -> usize {
     positon
 }
 
+/// Get ids of all scopes containing the node, from the innermost to the outermost.
+/// The chain always ends with the file itself, which has the id `scopes.len()`.
+pub fn get_scope_chain_by_node(node: &Node, scopes: &[Range]) -> Vec<usize> {
+    let node_range = node.range();
+    let mut chain: Vec<usize> = scopes
+        .iter()
+        .enumerate()
+        .filter(|(_, scope)| {
+            // a scope never contains itself
+            scope.start_byte <= node_range.start_byte
+                && node_range.end_byte <= scope.end_byte
+                && (scope.start_byte, scope.end_byte)
+                    != (node_range.start_byte, node_range.end_byte)
+        })
+        .map(|(pos, _)| pos)
+        .collect();
+    chain.sort_by_key(|pos| scopes[*pos].end_byte - scopes[*pos].start_byte);
+    chain.push(scopes.len());
+    chain
+}
+
+/// Get ids of all scopes containing the offset, from the innermost to the outermost.
+/// The chain always ends with the file itself, which has the id `scopes.len()`.
+pub fn get_scope_chain_by_offset(offset: usize, scopes: &[Range]) -> Vec<usize> {
+    let mut chain: Vec<usize> = scopes
+        .iter()
+        .enumerate()
+        .filter(|(_, scope)| {
+            // the root scope is still open at the end of the file
+            (scope.start_byte < offset || scope.start_byte == 0) && offset < scope.end_byte
+                || (scope.start_byte == 0 && offset == scope.end_byte)
+        })
+        .map(|(pos, _)| pos)
+        .collect();
+    chain.sort_by_key(|pos| scopes[*pos].end_byte - scopes[*pos].start_byte);
+    chain.push(scopes.len());
+    chain
+}
+
 pub mod embed {
+    use std::{cell::RefCell, collections::HashMap};
+
     use log::debug;
     // embed
     use rust_embed::RustEmbed;
@@ -45,14 +86,41 @@ pub mod embed {
     #[prefix = "basic/"]
     struct Asset;
 
+    /// Files which can be provided in the user query path, the other queries are always the
+    /// embeded ones
+    const USER_FILES: [&str; 2] = ["snippets.json", "lints.scm"];
+
+    thread_local! {
+        // the user query path is read once, a file added or changed later needs a restart
+        static USER_FILE_CACHE: RefCell<HashMap<(String, String), Option<String>>> =
+            RefCell::new(HashMap::new());
+    }
+
     /// Get queries in embeded files
     pub fn get_query_source(language_id: &str, source_type: &str) -> Option<String> {
+        get_file(language_id, &(source_type.to_owned() + ".scm"))
+    }
+
+    /// Get the snippets of the language, a JSON object of snippets by name
+    pub fn get_snippets_source(language_id: &str) -> Option<String> {
+        get_file(language_id, "snippets.json")
+    }
+
+    /// Get a file of the language, from the user query path, `<config dir>/tsls/queries`, first
+    /// for the files users may provide, and from the embeded files otherwise.
+    fn get_file(language_id: &str, file_name: &str) -> Option<String> {
+        if USER_FILES.contains(&file_name) {
+            if let Some(contents) = get_user_file(language_id, file_name) {
+                return Some(contents);
+            }
+        }
+
         let path = std::path::Path::new("basic/")
             .join(language_id)
-            .join(source_type.to_owned() + ".scm");
+            .join(file_name);
         debug!(
-            "get_query_source: language_id: {}, source_type: {}, path: {:?}",
-            language_id, source_type, path
+            "get_file: language_id: {}, file_name: {}, path: {:?}",
+            language_id, file_name, path
         );
 
         // TODO: Error handling, if file not found, return None
@@ -64,4 +132,22 @@ pub mod embed {
         .unwrap();
         Some(contents)
     }
+
+    /// Read a file of the language in the user query path, None if there's none.
+    fn get_user_file(language_id: &str, file_name: &str) -> Option<String> {
+        let key = (language_id.to_string(), file_name.to_string());
+        USER_FILE_CACHE.with(|cache| {
+            cache
+                .borrow_mut()
+                .entry(key)
+                .or_insert_with(|| {
+                    let path = dirs::config_dir()?
+                        .join("tsls/queries")
+                        .join(language_id)
+                        .join(file_name);
+                    std::fs::read_to_string(path).ok()
+                })
+                .clone()
+        })
+    }
 }
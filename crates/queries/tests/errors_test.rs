@@ -0,0 +1,79 @@
+use helper::tree_mutator::get_parser;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use queries::errors::{build_diagnostics, MISSING_CODE, SOURCE, UNEXPECTED_CODE};
+use tree_sitter::Tree;
+
+fn parse(text: &str) -> Tree {
+    get_parser("c".to_string())
+        .unwrap()
+        .parse(text, None)
+        .unwrap()
+}
+
+fn diagnostics(text: &str) -> Vec<Diagnostic> {
+    build_diagnostics(text.as_bytes().to_vec(), &parse(text).root_node())
+}
+
+fn error(start: (u32, u32), end: (u32, u32), message: &str, code: &str) -> Diagnostic {
+    Diagnostic {
+        range: Range::new(Position::new(start.0, start.1), Position::new(end.0, end.1)),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String(code.to_string())),
+        source: Some(SOURCE.to_string()),
+        message: message.to_string(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn missing_semicolon() {
+    let text = "int main() {\n  int a = 1\n  return a;\n}\n";
+    assert_eq!(
+        diagnostics(text),
+        vec![error((1, 11), (1, 11), "missing `;`", MISSING_CODE)]
+    );
+}
+
+#[test]
+fn unexpected_token() {
+    // in what the parser was in, and the token it then had to insert
+    let text = "int main() {\n  x = (1 + (2 * ) 3);\n}\n";
+    assert_eq!(
+        diagnostics(text),
+        vec![
+            error(
+                (1, 16),
+                (1, 17),
+                "unexpected `)` in binary expression",
+                UNEXPECTED_CODE
+            ),
+            error((1, 20), (1, 20), "missing `)`", MISSING_CODE),
+        ]
+    );
+
+    // at the top level of the file
+    let text = "int main() {\n  if (a) { b(); }}\n}\n";
+    assert_eq!(
+        diagnostics(text),
+        vec![error((2, 0), (2, 1), "unexpected `}`", UNEXPECTED_CODE)]
+    );
+}
+
+#[test]
+fn nested_errors_reported_once() {
+    // a single missing `)` makes the parser skip the loop and the increment in it
+    let text = "int main() {\n  for (int i = 0; i < 10; i++ {\n    a();\n  }\n}\n";
+    assert_eq!(
+        parse(text).root_node().to_sexp().matches("(ERROR").count(),
+        2
+    );
+    assert_eq!(
+        diagnostics(text),
+        vec![error(
+            (1, 2),
+            (3, 3),
+            "unexpected `for` in compound statement",
+            UNEXPECTED_CODE
+        )]
+    );
+}
@@ -0,0 +1,85 @@
+use helper::tree_mutator::get_parser;
+use lsp_types::{Position, Range, TextEdit};
+use queries::indents::{build_indent_captures, get_indent_edit, split_lines, text_edits};
+use tree_sitter::Tree;
+
+fn parse(text: &str) -> Tree {
+    get_parser("c".to_string())
+        .unwrap()
+        .parse(text, None)
+        .unwrap()
+}
+
+fn indent(text: &str, rows: std::ops::Range<usize>, insert_spaces: bool) -> Vec<TextEdit> {
+    text_edits(text.as_bytes(), "c", &parse(text), rows, 4, insert_spaces).unwrap()
+}
+
+fn edit(row: u32, end: u32, new_text: &str) -> TextEdit {
+    TextEdit {
+        range: Range {
+            start: Position::new(row, 0),
+            end: Position::new(row, end),
+        },
+        new_text: new_text.to_string(),
+    }
+}
+
+#[test]
+fn indents_captured_by_query() {
+    let text = "int main() {\n}\n";
+    let tree = parse(text);
+    assert!(build_indent_captures(text.as_bytes(), &tree.root_node(), "c").is_ok());
+    // no indents query, the document is left alone
+    assert!(build_indent_captures(text.as_bytes(), &tree.root_node(), "none").is_err());
+}
+
+#[test]
+fn block_indent() {
+    let text = "int main() {\nreturn 0;\n}\n";
+    assert_eq!(indent(text, 0..3, true), vec![edit(1, 0, "    ")]);
+    assert_eq!(indent(text, 0..3, false), vec![edit(1, 0, "\t")]);
+}
+
+#[test]
+fn dedent_closing_delimiter() {
+    let text = "int main() {\n    a();\n    }\n";
+    assert_eq!(indent(text, 0..3, true), vec![edit(2, 4, "")]);
+}
+
+#[test]
+fn aligned_continuation() {
+    // aligned after the opening parenthesis, which moves with its re-indented line
+    let text = "int main() {\n  foo(a,\nb);\n}\n";
+    assert_eq!(
+        indent(text, 0..4, true),
+        vec![edit(1, 2, "    "), edit(2, 0, "        ")]
+    );
+
+    // a tab spans the tab size
+    let text = "int main() {\n\tfoo(a,\nb);\n}\n";
+    assert_eq!(indent(text, 0..4, false), vec![edit(2, 0, "\t\t")]);
+}
+
+#[test]
+fn partial_range() {
+    let text = "int main() {\na();\nb();\n}\n";
+    assert_eq!(indent(text, 1..2, true), vec![edit(1, 0, "    ")]);
+    assert_eq!(indent(text, 2..3, true), vec![edit(2, 0, "    ")]);
+}
+
+#[test]
+fn indent_edit_of_whitespace() {
+    let lines = split_lines(b"  a\n\t\tb");
+    assert_eq!(
+        get_indent_edit(&lines, 0, 4, 4, true),
+        Some(edit(0, 2, "    "))
+    );
+    // already indented
+    assert_eq!(get_indent_edit(&lines, 1, 8, 4, false), None);
+    // tabs and the remaining spaces
+    assert_eq!(
+        get_indent_edit(&lines, 1, 6, 4, false),
+        Some(edit(1, 2, "\t  "))
+    );
+    assert_eq!(get_indent_edit(&lines, 2, 4, 4, true), None);
+}
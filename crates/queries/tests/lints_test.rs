@@ -0,0 +1,85 @@
+use helper::tree_mutator::get_parser;
+use lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Position, Range};
+use queries::{errors::SOURCE, lints::build_lint_diagnostics_by_query_source};
+
+const LINTS: &str = r#"
+((call_expression
+   function: (identifier) @_function) @diagnostic
+ (#eq? @_function "strcpy")
+ (#set! message "use strncpy instead of strcpy")
+ (#set! severity "error")
+ (#set! code "no-strcpy"))
+
+((call_expression
+   function: (identifier) @_function) @diagnostic
+ (#eq? @_function "gets"))
+"#;
+
+const TEXT: &str = "int main() {\n  strcpy(a, b);\n  gets(a);\n  strncpy(a, b, 1);\n}\n";
+
+fn lint(rules: &[&str]) -> Vec<Diagnostic> {
+    let tree = get_parser("c".to_string())
+        .unwrap()
+        .parse(TEXT, None)
+        .unwrap();
+    let rules: Vec<String> = rules.iter().map(|rule| rule.to_string()).collect();
+    build_lint_diagnostics_by_query_source(
+        &TEXT.as_bytes().to_vec(),
+        &tree.root_node(),
+        LINTS,
+        &rules,
+    )
+}
+
+fn call(line: u32, end: u32) -> Range {
+    Range::new(Position::new(line, 2), Position::new(line, end))
+}
+
+#[test]
+fn properties_of_rules() {
+    assert_eq!(
+        lint(&[]),
+        vec![
+            Diagnostic {
+                range: call(1, 14),
+                severity: Some(DiagnosticSeverity::ERROR),
+                code: Some(NumberOrString::String("no-strcpy".to_string())),
+                source: Some(SOURCE.to_string()),
+                message: "use strncpy instead of strcpy".to_string(),
+                ..Default::default()
+            },
+            // a warning by default
+            Diagnostic {
+                range: call(2, 9),
+                severity: Some(DiagnosticSeverity::WARNING),
+                source: Some(SOURCE.to_string()),
+                message: "lint".to_string(),
+                ..Default::default()
+            },
+        ]
+    );
+}
+
+#[test]
+fn enabled_rules() {
+    let diagnostics = lint(&["no-strcpy"]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].range, call(1, 14));
+    // rules without a code can't be enabled
+    assert!(lint(&["no-gets"]).is_empty());
+}
+
+#[test]
+fn invalid_query() {
+    let tree = get_parser("c".to_string())
+        .unwrap()
+        .parse(TEXT, None)
+        .unwrap();
+    let diagnostics = build_lint_diagnostics_by_query_source(
+        &TEXT.as_bytes().to_vec(),
+        &tree.root_node(),
+        "(call_expression",
+        &[],
+    );
+    assert!(diagnostics.is_empty());
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+
+use helper::{tree_mutator::get_parser, types::Symbol};
+use queries::locals::{
+    build_definitions_and_scopes, find_definition, find_rename_conflicts, find_visible_definitions,
+};
+use tree_sitter::{Range, Tree};
+
+struct Locals {
+    text: String,
+    tree: Tree,
+    definitions: HashMap<String, Vec<Symbol>>,
+    scopes: Vec<Range>,
+}
+
+fn build(text: &str, language_id: &str) -> Locals {
+    let tree = get_parser(language_id.to_string())
+        .unwrap()
+        .parse(text, None)
+        .unwrap();
+    let (definitions, scopes, _) =
+        build_definitions_and_scopes(&text.as_bytes().to_vec(), &tree.root_node(), language_id);
+    Locals {
+        text: text.to_string(),
+        tree,
+        definitions,
+        scopes,
+    }
+}
+
+impl Locals {
+    /// Start bytes of the occurrences of the binding the nth occurrence of the name refers to.
+    fn lookup(&self, name: &str, nth: usize) -> Option<Vec<usize>> {
+        let offset = self.text.match_indices(name).nth(nth).unwrap().0;
+        let node = self
+            .tree
+            .root_node()
+            .named_descendant_for_byte_range(offset, offset + name.len())
+            .unwrap();
+        find_definition(&self.definitions, name, &node, &self.scopes).map(|occurrences| {
+            occurrences
+                .iter()
+                .map(|symbol| symbol.location.start_byte)
+                .collect()
+        })
+    }
+
+    fn offsets(&self, name: &str, nths: &[usize]) -> Vec<usize> {
+        let offsets: Vec<usize> = self.text.match_indices(name).map(|(i, _)| i).collect();
+        nths.iter().map(|nth| offsets[*nth]).collect()
+    }
+
+    fn visible(&self, offset: usize, language_id: &str) -> Vec<&str> {
+        let mut names: Vec<&str> = find_visible_definitions(
+            &self.definitions,
+            offset,
+            &self.scopes,
+            &self.tree.root_node(),
+            language_id,
+        )
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+        names.sort();
+        names
+    }
+
+    /// Start bytes of the conflicts of renaming the binding of the nth occurrence of the name.
+    fn rename_conflicts(
+        &self,
+        name: &str,
+        nth: usize,
+        new_name: &str,
+        language_id: &str,
+    ) -> Vec<usize> {
+        let occurrences: Vec<Range> = self
+            .lookup(name, nth)
+            .unwrap()
+            .iter()
+            .map(|offset| {
+                self.tree
+                    .root_node()
+                    .named_descendant_for_byte_range(*offset, offset + name.len())
+                    .unwrap()
+                    .range()
+            })
+            .collect();
+        find_rename_conflicts(
+            &self.text.as_bytes().to_vec(),
+            &self.tree.root_node(),
+            language_id,
+            &occurrences,
+            new_name,
+        )
+        .iter()
+        .map(|range| range.start_byte)
+        .collect()
+    }
+}
+
+#[test]
+fn shadowed_in_block() {
+    let locals = build(
+        "int main() {\n  int x = 1;\n  {\n    int x = 2;\n    x++;\n  }\n  return x;\n}\n",
+        "c",
+    );
+    let outer = locals.offsets("x", &[0, 3]);
+    let inner = locals.offsets("x", &[1, 2]);
+    assert_eq!(locals.lookup("x", 0), Some(outer.clone()));
+    assert_eq!(locals.lookup("x", 1), Some(inner.clone()));
+    assert_eq!(locals.lookup("x", 2), Some(inner));
+    assert_eq!(locals.lookup("x", 3), Some(outer));
+}
+
+#[test]
+fn shadowed_parameter() {
+    let locals = build("x = 1\ndef f(x):\n    return x\nprint(x)\n", "python");
+    assert_eq!(locals.lookup("x", 2), Some(locals.offsets("x", &[1, 2])));
+    assert_eq!(locals.lookup("x", 3), Some(locals.offsets("x", &[0, 3])));
+}
+
+#[test]
+fn function_visible_outside_its_body() {
+    let locals = build("void f() {}\nint main() {\n  f();\n}\n", "c");
+    assert_eq!(locals.lookup("f", 1), Some(locals.offsets("f", &[0, 1])));
+}
+
+#[test]
+fn used_before_declaration_in_block() {
+    // the use binds to the outer x, the inner one is not declared yet
+    let locals = build(
+        "int main() {\n  int x = 1;\n  {\n    x++;\n    int x = 2;\n  }\n}\n",
+        "c",
+    );
+    assert_eq!(locals.lookup("x", 1), Some(locals.offsets("x", &[0, 1])));
+    assert_eq!(locals.lookup("x", 2), Some(locals.offsets("x", &[2])));
+}
+
+#[test]
+fn visible_before_declaration() {
+    let text = "int main() {\n  int a = 1;\n  \n  int b = 2;\n}\n";
+    let locals = build(text, "c");
+    let offset = text.find("\n  \n").unwrap() + 3;
+    assert!(locals.visible(offset, "c").contains(&"a"));
+    assert!(!locals.visible(offset, "c").contains(&"b"));
+    // top-level bindings are visible from anywhere in the file
+    let text = "int main() {\n  \n}\nint b = 2;\n";
+    let locals = build(text, "c");
+    let offset = text.find("\n  \n").unwrap() + 3;
+    assert!(locals.visible(offset, "c").contains(&"b"));
+}
+
+/// Names visible on the empty line of the text.
+fn visible_on_empty_line(text: &str, language_id: &str) -> Vec<String> {
+    let locals = build(text, language_id);
+    let offset = text.find("\n  \n").unwrap() + 3;
+    locals
+        .visible(offset, language_id)
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn javascript_hoisting() {
+    let visible = visible_on_empty_line(
+        "function main() {\n  let a = 1;\n  \n  const b = 2;\n  let c = 3;\n  class D {}\n  var e = 4;\n  function f() {}\n}\n",
+        "javascript",
+    );
+    assert_eq!(visible, vec!["a", "e", "f", "main"]);
+}
+
+#[test]
+fn typescript_hoisting() {
+    let visible = visible_on_empty_line(
+        "function main(): void {\n  const a: number = 1;\n  \n  const b: number = 2;\n  var c: number = 3;\n  function d(): void {}\n}\n",
+        "typescript",
+    );
+    assert_eq!(visible, vec!["a", "c", "d", "main"]);
+}
+
+#[test]
+fn python_function_scope() {
+    // bound anywhere in the function, e.g. by a previous iteration
+    let visible = visible_on_empty_line(
+        "def main():\n    for i in range(2):\n  \n        b = i\n",
+        "python",
+    );
+    assert!(visible.contains(&"b".to_string()));
+}
+
+#[test]
+fn rename_captures_outer_binding() {
+    // the global y would be shadowed, and its use refer to the renamed x
+    let locals = build(
+        "int y = 0;\nint main() {\n  int x = 1;\n  return x + y;\n}\n",
+        "c",
+    );
+    assert_eq!(
+        locals.rename_conflicts("x", 0, "y", "c"),
+        locals.offsets("y", &[0, 1])
+    );
+}
+
+#[test]
+fn rename_captured_by_inner_binding() {
+    // the use of x in the block would refer to the inner y
+    let locals = build(
+        "int main() {\n  int x = 1;\n  {\n    int y = 2;\n    x++;\n  }\n}\n",
+        "c",
+    );
+    assert_eq!(
+        locals.rename_conflicts("x", 0, "y", "c"),
+        locals.offsets("y", &[0])
+    );
+}
+
+#[test]
+fn rename_without_conflicts() {
+    // the other y is not visible from any occurrence of x
+    let locals = build(
+        "int main() {\n  {\n    int y = 2;\n  }\n  int x = 1;\n  x++;\n}\n",
+        "c",
+    );
+    assert!(locals.rename_conflicts("x", 0, "y", "c").is_empty());
+}
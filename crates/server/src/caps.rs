@@ -1,13 +1,26 @@
+use lsp_server::{Request, RequestId};
 use lsp_types::{
-    ClientCapabilities, CompletionOptions, DeclarationCapability, OneOf, SaveOptions,
-    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
-    TextDocumentSyncSaveOptions, WorkDoneProgressOptions,
+    notification::{DidChangeWatchedFiles, Notification},
+    request::{RegisterCapability, Request as _},
+    CallHierarchyServerCapability, ClientCapabilities, CodeActionKind, CodeActionOptions,
+    CodeActionProviderCapability, CodeLensOptions, CompletionOptions, DeclarationCapability,
+    DidChangeWatchedFilesRegistrationOptions, DocumentLinkOptions, DocumentOnTypeFormattingOptions,
+    ExecuteCommandOptions, FileSystemWatcher, GlobPattern, ImplementationProviderCapability,
+    InlayHintOptions, InlayHintServerCapabilities, LinkedEditingRangeServerCapabilities, OneOf,
+    Registration, RegistrationParams, RenameOptions, SaveOptions, ServerCapabilities,
+    TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncOptions,
+    TextDocumentSyncSaveOptions, TypeDefinitionProviderCapability, WorkDoneProgressOptions,
+    WorkspaceSymbolOptions,
 };
 
+/// The command of the server which does nothing, e.g. for lenses which only show a count
+pub const NO_OP_COMMAND: &str = "tsls.noop";
+
 /// The capabilities provided by the client (editor)
 /// use config to enable/disable capabilities
 pub fn new(client_caps: ClientCapabilities) -> ServerCapabilities {
     let server_caps = ServerCapabilities {
+        position_encoding: None,
         text_document_sync: Some(TextDocumentSyncCapability::Options(
             TextDocumentSyncOptions {
                 open_close: Some(true),
@@ -21,9 +34,15 @@ pub fn new(client_caps: ClientCapabilities) -> ServerCapabilities {
             },
         )),
         completion_provider: Some(CompletionOptions {
-            resolve_provider: None,
-            trigger_characters: Some(vec![".".to_string()]),
+            resolve_provider: Some(true),
+            trigger_characters: Some(
+                [".", ">", ":", "/", "\"", "<"]
+                    .iter()
+                    .map(|c| c.to_string())
+                    .collect(),
+            ),
             all_commit_characters: None,
+            completion_item: None,
             work_done_progress_options: WorkDoneProgressOptions {
                 work_done_progress: None,
             },
@@ -31,35 +50,110 @@ pub fn new(client_caps: ClientCapabilities) -> ServerCapabilities {
         declaration_provider: Some(DeclarationCapability::Simple(true)),
         definition_provider: Some(OneOf::Left(true)),
         references_provider: Some(OneOf::Left(true)),
-        rename_provider: None,
-        document_symbol_provider: None,
+        rename_provider: Some(OneOf::Right(RenameOptions {
+            prepare_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions::default(),
+        })),
+        document_symbol_provider: Some(OneOf::Left(true)),
         hover_provider: None,
         signature_help_provider: None,
-        type_definition_provider: None,
-        implementation_provider: None,
-        document_highlight_provider: None,
-        workspace_symbol_provider: None,
-        code_action_provider: None,
-        code_lens_provider: None,
+        type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+        implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
+        document_highlight_provider: Some(OneOf::Left(true)),
+        workspace_symbol_provider: Some(OneOf::Right(WorkspaceSymbolOptions {
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+            resolve_provider: Some(true),
+        })),
+        code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
+            code_action_kinds: Some(vec![
+                CodeActionKind::REFACTOR_EXTRACT,
+                CodeActionKind::REFACTOR_INLINE,
+            ]),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+            resolve_provider: None,
+        })),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(true),
+        }),
         document_formatting_provider: Some(OneOf::Left(true)),
-        document_range_formatting_provider: None,
-        document_on_type_formatting_provider: None,
+        document_range_formatting_provider: Some(OneOf::Left(true)),
+        document_on_type_formatting_provider: Some(DocumentOnTypeFormattingOptions {
+            first_trigger_character: "\n".to_string(),
+            more_trigger_character: Some(vec!["}".to_string(), ")".to_string()]),
+        }),
         selection_range_provider: None,
         folding_range_provider: None,
-        linked_editing_range_provider: None,
-        document_link_provider: None,
+        linked_editing_range_provider: Some(LinkedEditingRangeServerCapabilities::Simple(true)),
+        document_link_provider: Some(DocumentLinkOptions {
+            resolve_provider: Some(true),
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         color_provider: None,
-        execute_command_provider: None,
+        execute_command_provider: Some(ExecuteCommandOptions {
+            commands: vec![NO_OP_COMMAND.to_string()],
+            work_done_progress_options: WorkDoneProgressOptions {
+                work_done_progress: None,
+            },
+        }),
         workspace: None,
-        call_hierarchy_provider: None,
+        call_hierarchy_provider: Some(CallHierarchyServerCapability::Simple(true)),
         semantic_tokens_provider: None,
         moniker_provider: None,
+        inline_value_provider: None,
+        inlay_hint_provider: Some(OneOf::Right(InlayHintServerCapabilities::Options(
+            InlayHintOptions {
+                work_done_progress_options: WorkDoneProgressOptions {
+                    work_done_progress: None,
+                },
+                resolve_provider: Some(true),
+            },
+        ))),
+        diagnostic_provider: None,
         experimental: None,
     };
 
     merge_capabilities(client_caps, server_caps)
 }
 
+/// Ask the client to notify the changes of the files of the workspace, to keep the workspace
+/// index up to date. None if the client can't register it dynamically.
+pub fn watched_files_registration(client_caps: &ClientCapabilities) -> Option<Request> {
+    let is_supported = client_caps
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+        .and_then(|watched_files| watched_files.dynamic_registration)
+        .unwrap_or(false);
+    if !is_supported {
+        return None;
+    }
+
+    let register_options = DidChangeWatchedFilesRegistrationOptions {
+        watchers: vec![FileSystemWatcher {
+            glob_pattern: GlobPattern::String("**/*".to_string()),
+            kind: None,
+        }],
+    };
+    let params = RegistrationParams {
+        registrations: vec![Registration {
+            id: DidChangeWatchedFiles::METHOD.to_string(),
+            method: DidChangeWatchedFiles::METHOD.to_string(),
+            register_options: serde_json::to_value(register_options).ok(),
+        }],
+    };
+    Some(Request::new(
+        RequestId::from(RegisterCapability::METHOD.to_string()),
+        RegisterCapability::METHOD.to_string(),
+        params,
+    ))
+}
+
 fn merge_capabilities(
     _client_caps: ClientCapabilities,
     server_caps: ServerCapabilities,
@@ -1,7 +1,7 @@
 use log::warn;
 use lsp_types::TextDocumentItem;
 use queries::{
-    errors,
+    children::build_document_symbols,
     highlight::{self, update_identifiers_kind},
     locals::build_definitions_and_scopes,
 };
@@ -37,6 +37,12 @@ impl GlobalState {
 
         let keywords = highlight::build_keywords_cache(source_code.language_id.clone());
 
+        let document_symbols = build_document_symbols(
+            &source_code.text.as_bytes().to_vec(),
+            &tree.root_node(),
+            &source_code.language_id,
+        );
+
         // Save it to the global state
         let properties = Properties {
             ast: tree.to_owned(),
@@ -47,15 +53,16 @@ impl GlobalState {
             ordered_scopes,
             definitions_lookup_map,
             identifiers,
+            document_symbols,
         };
 
         // insert update the value in hashmap
         self.sources.insert(source_code.uri.clone(), properties);
+        self.update_workspace_index(&source_code.uri);
 
-        let diagnostics =
-            errors::build_diagnostics(source_code.text.as_bytes().to_vec(), &tree.root_node());
-
-        self.diagnostics.insert(source_code.uri, diagnostics);
+        if let Err(e) = self.update_diagnostics(&source_code.uri) {
+            warn!("could not update diagnostics of {}: {}", source_code.uri, e);
+        }
     }
 
     // WARN: Not used for now
@@ -0,0 +1,93 @@
+use log::error;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Settings of the server, from the initialization options and
+/// workspace/didChangeConfiguration, e.g. `{ "inlayHints": { "parameterNames": false } }`.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Config {
+    pub inlay_hints: InlayHintsConfig,
+    // directories searched for included files and imported modules,
+    // relative ones are resolved against every workspace folder
+    pub include_paths: Vec<String>,
+    pub lints: LintsConfig,
+    pub code_lens: CodeLensConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CodeLensConfig {
+    // command of the client which lists the references, given the uri, the position and the
+    // locations, the lenses only show the count when it's null
+    pub show_references_command: Option<String>,
+}
+
+impl Default for CodeLensConfig {
+    fn default() -> Self {
+        CodeLensConfig {
+            show_references_command: Some("editor.action.showReferences".to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LintsConfig {
+    // evaluate the rules of the lints queries, e.g. in the settings of a project
+    pub enable: bool,
+    // codes of the rules to evaluate, all of them if empty
+    pub rules: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InlayHintsConfig {
+    // label the arguments of calls with the names of the parameters
+    pub parameter_names: bool,
+}
+
+impl Default for InlayHintsConfig {
+    fn default() -> Self {
+        InlayHintsConfig {
+            parameter_names: true,
+        }
+    }
+}
+
+impl Config {
+    /// Update the settings present in the payload, the others are kept. They may be nested in a
+    /// `tsls` section.
+    pub fn update(&mut self, settings: &Value) {
+        let settings = settings.get("tsls").unwrap_or(settings);
+        let mut merged = match serde_json::to_value(&*self) {
+            Ok(current) => current,
+            Err(e) => {
+                error!("could not serialize settings {:?}: {}", self, e);
+                return;
+            }
+        };
+        merge(&mut merged, settings);
+        match serde_json::from_value::<Config>(merged) {
+            Ok(config) => *self = config,
+            Err(e) => error!("invalid settings {:?}: {}", settings, e),
+        }
+    }
+}
+
+/// Merge the keys of the settings into the base, objects recursively.
+fn merge(base: &mut Value, settings: &Value) {
+    match (base, settings) {
+        (Value::Object(base), Value::Object(settings)) => {
+            for (key, value) in settings {
+                match base.get_mut(key) {
+                    Some(base_value) => merge(base_value, value),
+                    None => {
+                        base.insert(key.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        (base, settings) => *base = settings.clone(),
+    }
+}
@@ -1,4 +1,6 @@
 pub mod cache;
+mod config;
 mod state;
+mod workspace;
 
-pub use self::state::*;
+pub use self::{config::*, state::*, workspace::*};
@@ -1,14 +1,19 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, rc::Rc};
 
 use helper::types::Symbol;
 use log::warn;
-use lsp_types::{Diagnostic, Position, Url};
-use queries::errors::build_diagnostics;
+use lsp_types::{ClientCapabilities, Diagnostic, InitializeParams, Position, Url};
+use queries::{errors::build_diagnostics, lints::build_lint_diagnostics};
 use tree_sitter::{Node, Range, Tree};
 
+use crate::global_state::{Config, IndexedFile, WorkspaceIndex};
+
 type Byte = u8;
 type ScopeID = usize;
 
+/// Languages whose files share top-level bindings, e.g. through headers
+const LANGUAGE_FAMILIES: [&[&str]; 1] = [&["c", "cpp", "cuda"]];
+
 #[derive(Debug, Clone)]
 pub struct Properties {
     pub ast: Tree,
@@ -22,12 +27,15 @@ pub struct Properties {
     pub ordered_scopes: Vec<Range>,
     pub definitions_lookup_map: HashMap<String, Vec<Symbol>>,
     pub identifiers: HashMap<ScopeID, Vec<Symbol>>,
+    // the outline of the document, definitions nested by containment
+    pub document_symbols: Vec<Symbol>,
 }
 
 impl Properties {
     pub fn clear(&mut self) {
         self.identifiers.clear();
         self.definitions_lookup_map.clear();
+        self.document_symbols.clear();
         self.ordered_scopes.clear();
         self.keywords.clear();
         self.source_code.clear();
@@ -42,14 +50,75 @@ impl Properties {
 pub struct GlobalState {
     pub sources: HashMap<Url, Properties>,
     pub diagnostics: HashMap<Url, Vec<Diagnostic>>,
+    pub client_capabilities: ClientCapabilities,
+    pub config: Config,
+    // shared by snapshots, it's expensive to clone
+    pub workspace_index: Rc<WorkspaceIndex>,
+    // files changed on disk while the workspace is indexed in the background, None otherwise
+    pub pending_watched_files: Option<Vec<Url>>,
 }
 
 impl GlobalState {
-    /// Create a new GlobalState
-    pub fn new() -> Self {
+    /// Create a new GlobalState from the initialize params
+    pub fn new(params: &InitializeParams) -> Self {
+        let mut config = Config::default();
+        if let Some(initialization_options) = &params.initialization_options {
+            config.update(initialization_options);
+        }
+
         GlobalState {
             sources: HashMap::new(),
             diagnostics: HashMap::new(),
+            client_capabilities: params.capabilities.clone(),
+            config,
+            workspace_index: Rc::new(WorkspaceIndex::new(params)),
+            pending_watched_files: None,
+        }
+    }
+
+    /// Add the files of the workspace indexed in the background, the opened documents indexed
+    /// meanwhile are kept as they are newer. The files changed on disk meanwhile are indexed
+    /// again, the background index may have read them before the change or after a deletion.
+    pub fn extend_workspace_index(&mut self, files: HashMap<Url, IndexedFile>) {
+        let workspace_index = Rc::make_mut(&mut self.workspace_index);
+        for (uri, file) in files {
+            workspace_index.files.entry(uri).or_insert(file);
+        }
+        for uri in self.pending_watched_files.take().unwrap_or_default() {
+            self.update_watched_file(&uri);
+        }
+    }
+
+    /// Update the workspace index with a file changed on disk, opened documents are kept up to
+    /// date by the client instead
+    pub fn update_watched_file(&mut self, uri: &Url) {
+        let is_opened = self
+            .sources
+            .get(uri)
+            .is_some_and(|properties| !properties.language_id.is_empty());
+        if is_opened {
+            return;
+        }
+        if let Some(pending_watched_files) = &mut self.pending_watched_files {
+            pending_watched_files.push(uri.clone());
+        }
+        if let Ok(path) = uri.to_file_path() {
+            Rc::make_mut(&mut self.workspace_index).reindex_path(&path);
+        }
+    }
+
+    /// Update the workspace index with the cached tree of an opened document
+    pub fn update_workspace_index(&mut self, uri: &Url) {
+        if !self.workspace_index.contains(uri) {
+            return;
+        }
+        if let Some(properties) = self.sources.get(uri) {
+            Rc::make_mut(&mut self.workspace_index).update_file(
+                uri.clone(),
+                &properties.language_id,
+                properties.source_code.clone(),
+                properties.ast.clone(),
+            );
         }
     }
 
@@ -58,16 +127,24 @@ impl GlobalState {
         self.diagnostics.get(uri).cloned()
     }
 
-    // update the diagnostics of a given url
+    // update the diagnostics of a given url, the syntax errors and the lints
     pub fn update_diagnostics(&mut self, uri: &lsp_types::Url) -> Result<(), String> {
         let properties = match self.sources.get(uri) {
             Some(properties) => properties,
             None => return Err("No properties found".into()),
         };
-        let diagnostics = build_diagnostics(
+        let mut diagnostics = build_diagnostics(
             properties.source_code.clone(),
             &self.sources.get(uri).unwrap().ast.root_node(),
         );
+        if self.config.lints.enable {
+            diagnostics.extend(build_lint_diagnostics(
+                &properties.source_code,
+                &properties.ast.root_node(),
+                &properties.language_id,
+                &self.config.lints.rules,
+            ));
+        }
         self.diagnostics.insert(uri.clone(), diagnostics);
         Ok(())
     }
@@ -86,6 +163,70 @@ impl GlobalState {
             .map(|properties| properties.ast.clone())
     }
 
+    /// Get the tree, source code and language_id of a file, opened or only indexed
+    pub fn get_file(&self, url: &Url) -> Option<(&Tree, &Vec<Byte>, &str)> {
+        // closed documents are cleared but kept around
+        if let Some(properties) = self
+            .sources
+            .get(url)
+            .filter(|properties| !properties.language_id.is_empty())
+        {
+            return Some((
+                &properties.ast,
+                &properties.source_code,
+                &properties.language_id,
+            ));
+        }
+        self.workspace_index
+            .files
+            .get(url)
+            .map(|file| (&file.ast, &file.source_code, file.language_id.as_str()))
+    }
+
+    /// Get every known file, the opened documents and the indexed files which are not opened
+    pub fn get_files(&self) -> Vec<(&Url, &Tree, &Vec<Byte>, &str)> {
+        let mut files: Vec<(&Url, &Tree, &Vec<Byte>, &str)> = self
+            .sources
+            .iter()
+            .filter(|(_, properties)| !properties.language_id.is_empty())
+            .map(|(url, properties)| {
+                (
+                    url,
+                    &properties.ast,
+                    &properties.source_code,
+                    properties.language_id.as_str(),
+                )
+            })
+            .collect();
+        for (url, file) in self.workspace_index.files.iter() {
+            if files.iter().all(|(opened, ..)| *opened != url) {
+                files.push((url, &file.ast, &file.source_code, &file.language_id));
+            }
+        }
+        files
+    }
+
+    /// Get the files of the language, or of one sharing its bindings, whose source code contains
+    /// the name, e.g. those which may refer to a binding. Empty for an empty name.
+    pub fn files_mentioning(
+        &self,
+        name: &str,
+        language_id: &str,
+    ) -> Vec<(&Url, &Tree, &Vec<Byte>, &str)> {
+        if name.is_empty() {
+            return vec![];
+        }
+        self.get_files()
+            .into_iter()
+            .filter(|(_, _, source_code, file_language_id)| {
+                is_same_language(language_id, file_language_id)
+                    && source_code
+                        .windows(name.len())
+                        .any(|window| window == name.as_bytes())
+            })
+            .collect()
+    }
+
     /// Get the source code of a given url, return None if not found, byte vector otherwise
     pub fn get_source_code(&self, url: &Url) -> Option<Vec<Byte>> {
         self.sources
@@ -100,6 +241,15 @@ impl GlobalState {
             .map(|properties| properties.language_id.to_string())
     }
 
+    /// Get the urls of the opened documents
+    pub fn get_opened_uris(&self) -> Vec<Url> {
+        self.sources
+            .iter()
+            .filter(|(_, properties)| !properties.language_id.is_empty())
+            .map(|(url, _)| url.clone())
+            .collect()
+    }
+
     /// Update the source code of a given url
     pub fn update_source_code(&mut self, url: &Url, new_source_code: Vec<Byte>) {
         if let Some(properties) = self.sources.get_mut(url) {
@@ -107,6 +257,13 @@ impl GlobalState {
         }
     }
 
+    /// Update the version of a given url, the one of the last change applied
+    pub fn update_version(&mut self, url: &Url, version: i32) {
+        if let Some(properties) = self.sources.get_mut(url) {
+            properties.version = version;
+        }
+    }
+
     pub fn update_tree(&mut self, url: &Url, new_tree: Tree) {
         if let Some(properties) = self.sources.get_mut(url) {
             properties.ast = new_tree;
@@ -151,3 +308,11 @@ impl GlobalState {
         self.diagnostics.clear();
     }
 }
+
+/// Whether files of the languages may refer to each other's top-level bindings.
+pub fn is_same_language(language_id: &str, other: &str) -> bool {
+    language_id == other
+        || LANGUAGE_FAMILIES
+            .iter()
+            .any(|family| family.contains(&language_id) && family.contains(&other))
+}
@@ -0,0 +1,191 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use helper::{
+    tree_mutator::{get_language_id_by_path, get_parser},
+    types::Symbol,
+};
+use log::{debug, error, warn};
+use lsp_types::{InitializeParams, Url};
+use queries::locals::build_definition_symbols;
+use tree_sitter::Tree;
+
+type Byte = u8;
+
+/// Directories that never contain sources worth indexing
+const IGNORED_DIRECTORIES: [&str; 3] = ["target", "node_modules", "build"];
+
+/// Files larger than this are most likely generated
+const MAX_FILE_SIZE: u64 = 1024 * 1024;
+
+/// A file of the workspace, parsed whether it's opened or not.
+#[derive(Debug, Clone)]
+pub struct IndexedFile {
+    pub ast: Tree,
+    pub language_id: String,
+    pub source_code: Vec<Byte>,
+    // definitions visible from other files
+    pub symbols: Vec<Symbol>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceIndex {
+    pub roots: Vec<PathBuf>,
+    pub files: HashMap<Url, IndexedFile>,
+}
+
+impl WorkspaceIndex {
+    /// Create an empty index of the workspace folders, fallback to the root uri.
+    pub fn new(params: &InitializeParams) -> Self {
+        let mut roots: Vec<PathBuf> = params
+            .workspace_folders
+            .iter()
+            .flatten()
+            .filter_map(|folder| folder.uri.to_file_path().ok())
+            .collect();
+
+        #[allow(deprecated)]
+        if roots.is_empty() {
+            if let Some(Ok(root)) = params.root_uri.as_ref().map(|uri| uri.to_file_path()) {
+                roots.push(root);
+            }
+        }
+
+        WorkspaceIndex {
+            roots,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Parse every supported file under the workspace folders.
+    pub fn build(&mut self) {
+        for root in self.roots.clone() {
+            self.index_directory(&root);
+        }
+        warn!("workspace index built: {} files", self.files.len());
+    }
+
+    fn index_directory(&mut self, directory: &Path) {
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                error!("could not read directory {:?}: {}", directory, e);
+                return;
+            }
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let file_name = entry.file_name();
+            let file_name = file_name.to_string_lossy();
+            let file_type = match entry.file_type() {
+                Ok(file_type) => file_type,
+                Err(_) => continue,
+            };
+
+            // hidden files, and symlinks which may loop
+            if file_name.starts_with('.') || file_type.is_symlink() {
+                continue;
+            }
+
+            if file_type.is_dir() {
+                if !IGNORED_DIRECTORIES.contains(&file_name.as_ref()) {
+                    self.index_directory(&path);
+                }
+                continue;
+            }
+
+            self.index_path(&path);
+        }
+    }
+
+    /// Read and parse a file of a supported language.
+    fn index_path(&mut self, path: &Path) {
+        let language_id = match get_language_id_by_path(path) {
+            Some(language_id) => language_id,
+            None => return,
+        };
+        if fs::metadata(path).map_or(true, |m| m.len() > MAX_FILE_SIZE) {
+            debug!("skip large file {:?}", path);
+            return;
+        }
+        let (uri, source_code) = match (Url::from_file_path(path), fs::read(path)) {
+            (Ok(uri), Ok(source_code)) => (uri, source_code),
+            _ => return,
+        };
+        self.index_file(uri, &language_id, source_code);
+    }
+
+    /// Index a file created or changed on disk again, or forget it and the files under it once
+    /// deleted.
+    pub fn reindex_path(&mut self, path: &Path) {
+        if self.is_ignored(path) {
+            return;
+        }
+        if path.is_file() {
+            self.index_path(path);
+        } else {
+            self.files.retain(|uri, _| {
+                uri.to_file_path()
+                    .map_or(true, |file_path| !file_path.starts_with(path))
+            });
+        }
+    }
+
+    /// Whether the path is outside of the workspace folders, or hidden or ignored in them
+    fn is_ignored(&self, path: &Path) -> bool {
+        let relative_path = match self
+            .roots
+            .iter()
+            .find_map(|root| path.strip_prefix(root).ok())
+        {
+            Some(relative_path) => relative_path,
+            None => return true,
+        };
+        relative_path.components().any(|component| {
+            let name = component.as_os_str().to_string_lossy();
+            name.starts_with('.') || IGNORED_DIRECTORIES.contains(&name.as_ref())
+        })
+    }
+
+    /// Parse a file and replace its entry.
+    pub fn index_file(&mut self, uri: Url, language_id: &str, source_code: Vec<Byte>) {
+        let mut parser = match get_parser(language_id.to_string()) {
+            Some(parser) => parser,
+            None => return,
+        };
+        let ast = match parser.parse(&source_code, None) {
+            Some(ast) => ast,
+            None => {
+                error!("Error while parsing {}", uri);
+                return;
+            }
+        };
+        self.update_file(uri, language_id, source_code, ast);
+    }
+
+    /// Replace the entry of an already parsed file, e.g. an opened document.
+    pub fn update_file(&mut self, uri: Url, language_id: &str, source_code: Vec<Byte>, ast: Tree) {
+        let symbols = build_definition_symbols(&source_code, &ast.root_node(), language_id);
+        self.files.insert(
+            uri,
+            IndexedFile {
+                ast,
+                language_id: language_id.to_string(),
+                source_code,
+                symbols,
+            },
+        );
+    }
+
+    /// Whether the file is under one of the workspace folders
+    pub fn contains(&self, uri: &Url) -> bool {
+        match uri.to_file_path() {
+            Ok(path) => self.roots.iter().any(|root| path.starts_with(root)),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use helper::{
+    convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position, types::Symbol,
+};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyIncomingCallsParams, CallHierarchyItem,
+    CallHierarchyOutgoingCall, CallHierarchyOutgoingCallsParams, CallHierarchyPrepareParams,
+    SymbolKind, Url,
+};
+use queries::{
+    calls::build_call_sites,
+    children::build_document_symbols,
+    locals::{build_definitions_and_scopes, find_definition, is_top_level_reference},
+};
+use tree_sitter::{Range, Tree};
+
+use crate::global_state::{is_same_language, GlobalState};
+
+/// Definitions which can be called
+const CALLABLE_KINDS: [SymbolKind; 3] = [
+    SymbolKind::FUNCTION,
+    SymbolKind::METHOD,
+    SymbolKind::CONSTRUCTOR,
+];
+
+/// Get the function at the position, or the functions the call at the position may resolve to.
+pub fn prepare_call_hierarchy(
+    id: RequestId,
+    params: CallHierarchyPrepareParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got prepare call hierarchy request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No node found for this position".to_string(),
+        );
+    };
+
+    // on the name of a definition
+    let functions = get_functions(tree, source_code, language_id);
+    let items: Vec<CallHierarchyItem> = match functions
+        .iter()
+        .find(|function| function.location.start_byte == node.start_byte())
+    {
+        Some(function) => vec![to_call_hierarchy_item(&uri, function)],
+        None => {
+            let name = node.utf8_text(source_code).unwrap_or_default();
+            resolve_functions(&state, &uri, node.range(), name, &mut HashMap::new())
+        }
+    };
+
+    let result = if items.is_empty() { None } else { Some(items) };
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the calls of the item in every known file, grouped by the function they are made in.
+pub fn incoming_calls(
+    id: RequestId,
+    params: CallHierarchyIncomingCallsParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got incoming calls request #{}: {:?}", id, params);
+
+    let item = params.item;
+    let item_language_id = match state.get_file(&item.uri) {
+        Some((_, _, language_id)) => language_id,
+        None => {
+            return Response::new_err(
+                id,
+                ParseError as i32,
+                "No tree found for this document".to_string(),
+            )
+        }
+    };
+    let mut incoming_calls: Vec<CallHierarchyIncomingCall> = Vec::new();
+
+    for (uri, tree, source_code, language_id) in
+        state.files_mentioning(&item.name, item_language_id)
+    {
+        let call_sites: Vec<Range> = build_call_sites(source_code, &tree.root_node(), language_id)
+            .into_iter()
+            .filter(|(name, _)| *name == item.name)
+            .map(|(_, range)| range)
+            .collect();
+        if call_sites.is_empty() {
+            continue;
+        }
+
+        // calls of a local binding with the name are no calls of the item, unless it's the item
+        let built;
+        let (definitions_lookup_map, scopes) = match state
+            .sources
+            .get(uri)
+            .filter(|properties| !properties.language_id.is_empty())
+        {
+            Some(properties) => (
+                &properties.definitions_lookup_map,
+                &properties.ordered_scopes,
+            ),
+            None => {
+                built = build_definitions_and_scopes(source_code, &tree.root_node(), language_id);
+                (&built.0, &built.1)
+            }
+        };
+        let call_sites: Vec<Range> = call_sites
+            .into_iter()
+            .filter(|call_site| {
+                let node = match tree
+                    .root_node()
+                    .named_descendant_for_byte_range(call_site.start_byte, call_site.end_byte)
+                {
+                    Some(node) => node,
+                    None => return false,
+                };
+                let is_item = |definition: &Symbol| {
+                    *uri == item.uri
+                        && ts_range_to_lsp_range(&definition.location) == item.selection_range
+                };
+                is_top_level_reference(
+                    definitions_lookup_map,
+                    &item.name,
+                    &node,
+                    scopes,
+                    &tree.root_node(),
+                ) || find_definition(definitions_lookup_map, &item.name, &node, scopes)
+                    .and_then(|occurrences| occurrences.first())
+                    .is_some_and(is_item)
+            })
+            .collect();
+        if call_sites.is_empty() {
+            continue;
+        }
+
+        let functions = get_functions(tree, source_code, language_id);
+        for call_site in call_sites {
+            // calls outside of functions are made by the file itself
+            let from = match get_enclosing_function(&functions, &call_site) {
+                Some(function) => to_call_hierarchy_item(uri, function),
+                None => to_file_item(uri, tree),
+            };
+            let from_range = ts_range_to_lsp_range(&call_site);
+
+            match incoming_calls
+                .iter_mut()
+                .find(|call| is_same_item(&call.from, &from))
+            {
+                Some(call) => call.from_ranges.push(from_range),
+                None => incoming_calls.push(CallHierarchyIncomingCall {
+                    from,
+                    from_ranges: vec![from_range],
+                }),
+            }
+        }
+    }
+
+    let result = Some(incoming_calls);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the calls made by the item, grouped by the function they resolve to.
+pub fn outgoing_calls(
+    id: RequestId,
+    params: CallHierarchyOutgoingCallsParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got outgoing calls request #{}: {:?}", id, params);
+
+    let item = params.item;
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&item.uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+
+    let functions = get_functions(tree, source_code, language_id);
+    let caller = functions
+        .iter()
+        .find(|function| ts_range_to_lsp_range(&function.location) == item.selection_range);
+
+    // the functions of each file, the document's and those of the files callees are defined in
+    let mut functions_by_file: HashMap<Url, Vec<Symbol>> = HashMap::new();
+    let mut outgoing_calls: Vec<CallHierarchyOutgoingCall> = Vec::new();
+    for (name, call_site) in build_call_sites(source_code, &tree.root_node(), language_id) {
+        // calls of nested functions are made by them
+        let enclosing_function = get_enclosing_function(&functions, &call_site);
+        let is_made_by_caller = match (caller, enclosing_function) {
+            (Some(caller), Some(function)) => caller == function,
+            (None, None) => true,
+            _ => false,
+        };
+        if !is_made_by_caller {
+            continue;
+        }
+
+        let from_range = ts_range_to_lsp_range(&call_site);
+        for to in resolve_functions(&state, &item.uri, call_site, &name, &mut functions_by_file) {
+            match outgoing_calls
+                .iter_mut()
+                .find(|call| is_same_item(&call.to, &to))
+            {
+                Some(call) => call.from_ranges.push(from_range),
+                None => outgoing_calls.push(CallHierarchyOutgoingCall {
+                    to,
+                    from_ranges: vec![from_range],
+                }),
+            }
+        }
+    }
+
+    let result = Some(outgoing_calls);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the functions a name may refer to, the local binding first, then the functions with that
+/// name of the document and of the indexed files of the same language. The functions of each file
+/// are built once into `functions_by_file`.
+fn resolve_functions(
+    state: &GlobalState,
+    uri: &Url,
+    range: Range,
+    name: &str,
+    functions_by_file: &mut HashMap<Url, Vec<Symbol>>,
+) -> Vec<CallHierarchyItem> {
+    let (tree, language_id) = match state.get_file(uri) {
+        Some((tree, _, language_id)) => (tree, language_id),
+        None => return vec![],
+    };
+
+    if let Some(properties) = state.sources.get(uri) {
+        let definition = tree
+            .root_node()
+            .named_descendant_for_byte_range(range.start_byte, range.end_byte)
+            .and_then(|node| {
+                find_definition(
+                    &properties.definitions_lookup_map,
+                    name,
+                    &node,
+                    &properties.ordered_scopes,
+                )
+            })
+            .and_then(|occurrences| occurrences.first());
+
+        if let Some(definition) = definition {
+            // a local variable shadows the functions of other files
+            return get_cached_functions(state, uri, functions_by_file)
+                .iter()
+                .filter(|function| function.location.start_byte == definition.location.start_byte)
+                .map(|function| to_call_hierarchy_item(uri, function))
+                .collect();
+        }
+    }
+
+    // the document may not be indexed, e.g. when it's outside of the workspace
+    let mut items: Vec<CallHierarchyItem> = get_cached_functions(state, uri, functions_by_file)
+        .iter()
+        .filter(|function| function.name == name)
+        .map(|function| to_call_hierarchy_item(uri, function))
+        .collect();
+
+    for (file_uri, file) in state.workspace_index.files.iter() {
+        if file_uri == uri
+            || !is_same_language(language_id, &file.language_id)
+            || !file.symbols.iter().any(|symbol| {
+                symbol.name == name
+                    && symbol
+                        .symbol_kind
+                        .iter()
+                        .any(|kind| CALLABLE_KINDS.contains(kind))
+            })
+        {
+            continue;
+        }
+        items.extend(
+            get_cached_functions(state, file_uri, functions_by_file)
+                .iter()
+                .filter(|function| function.name == name)
+                .map(|function| to_call_hierarchy_item(file_uri, function)),
+        );
+    }
+    items
+}
+
+/// Get the functions of a file, building them the first time.
+fn get_cached_functions<'a>(
+    state: &GlobalState,
+    uri: &Url,
+    functions_by_file: &'a mut HashMap<Url, Vec<Symbol>>,
+) -> &'a [Symbol] {
+    functions_by_file.entry(uri.clone()).or_insert_with(|| {
+        state
+            .get_file(uri)
+            .map(|(tree, source_code, language_id)| get_functions(tree, source_code, language_id))
+            .unwrap_or_default()
+    })
+}
+
+/// Get the functions and methods of the outline, nested ones included.
+fn get_functions(tree: &Tree, source_code: &Vec<u8>, language_id: &str) -> Vec<Symbol> {
+    let mut functions: Vec<Symbol> = Vec::new();
+    let mut stack = build_document_symbols(source_code, &tree.root_node(), language_id);
+    while let Some(mut symbol) = stack.pop() {
+        if let Some(children) = symbol.children.take() {
+            stack.extend(children);
+        }
+        if symbol
+            .symbol_kind
+            .iter()
+            .any(|kind| CALLABLE_KINDS.contains(kind))
+        {
+            functions.push(symbol);
+        }
+    }
+    functions
+}
+
+/// Get the innermost function whose definition contains the range.
+fn get_enclosing_function<'a>(functions: &'a [Symbol], range: &Range) -> Option<&'a Symbol> {
+    functions
+        .iter()
+        .filter(|function| {
+            let definition_range = function.definition_range.unwrap_or(function.location);
+            definition_range.start_byte <= range.start_byte
+                && range.end_byte <= definition_range.end_byte
+        })
+        .min_by_key(|function| {
+            let definition_range = function.definition_range.unwrap_or(function.location);
+            definition_range.end_byte - definition_range.start_byte
+        })
+}
+
+fn to_call_hierarchy_item(uri: &Url, function: &Symbol) -> CallHierarchyItem {
+    CallHierarchyItem {
+        name: function.name.clone(),
+        kind: *function.symbol_kind.last().unwrap(),
+        tags: None,
+        detail: function.detail.clone(),
+        uri: uri.clone(),
+        range: ts_range_to_lsp_range(&function.definition_range.unwrap_or(function.location)),
+        selection_range: ts_range_to_lsp_range(&function.location),
+        data: None,
+    }
+}
+
+/// The file itself, for calls made at the top level.
+fn to_file_item(uri: &Url, tree: &Tree) -> CallHierarchyItem {
+    let range = ts_range_to_lsp_range(&tree.root_node().range());
+    CallHierarchyItem {
+        name: uri
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .unwrap_or_default()
+            .to_string(),
+        kind: SymbolKind::FILE,
+        tags: None,
+        detail: None,
+        uri: uri.clone(),
+        range,
+        selection_range: lsp_types::Range {
+            start: range.start,
+            end: range.start,
+        },
+        data: None,
+    }
+}
+
+fn is_same_item(a: &CallHierarchyItem, b: &CallHierarchyItem) -> bool {
+    a.uri == b.uri && a.selection_range == b.selection_range
+}
@@ -0,0 +1,695 @@
+use std::collections::HashMap;
+
+use helper::{
+    convert::{lsp_range_to_ts_range, offset_to_position, ts_point_to_lsp_position},
+    tree_walker::get_named_node_by_position,
+};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    CodeAction, CodeActionDisabled, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionResponse, TextEdit, Url, WorkspaceEdit,
+};
+use queries::locals::{build_write_ranges, find_definition, find_visible_definitions};
+use tree_sitter::Node;
+
+use crate::global_state::{GlobalState, Properties};
+
+/// Name of the extracted variable, suffixed with a number when it's taken
+const EXTRACTED_NAME: &str = "extracted";
+
+/// Nodes whose children are statements executed in order
+const BLOCKS: [&str; 6] = [
+    "compound_statement",
+    "block",
+    "statement_block",
+    "statements",
+    "module",
+    "program",
+];
+
+/// Nodes which bind a single variable to an initializer
+const DECLARATORS: [&str; 6] = [
+    "init_declarator",
+    "let_declaration",
+    "variable_declarator",
+    "assignment",
+    "short_var_declaration",
+    "var_spec",
+];
+
+/// Expressions that need parentheses when they become an operand
+const COMPOUND_EXPRESSIONS: [&str; 12] = [
+    "binary_expression",
+    "unary_expression",
+    "conditional_expression",
+    "ternary_expression",
+    "assignment_expression",
+    "range_expression",
+    "cast_expression",
+    "binary_operator",
+    "boolean_operator",
+    "comparison_operator",
+    "not_operator",
+    "lambda",
+];
+
+pub fn code_action(id: RequestId, params: CodeActionParams, state: GlobalState) -> Response {
+    debug!("got code action request #{}: {:?}", id, params);
+
+    let uri = params.text_document.uri;
+    let properties = if let Some(properties) = state.sources.get(&uri) {
+        properties
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No properties found for this document".to_string(),
+        );
+    };
+
+    // refused actions are only shown to clients that can grey them out
+    let disabled_support = state
+        .client_capabilities
+        .text_document
+        .as_ref()
+        .and_then(|text_document| text_document.code_action.as_ref())
+        .and_then(|code_action| code_action.disabled_support)
+        .unwrap_or(false);
+
+    let mut actions: Vec<CodeAction> = Vec::new();
+
+    let selection = lsp_range_to_ts_range(&params.range, &properties.source_code);
+    if selection.start_byte < selection.end_byte {
+        actions.push(to_code_action(
+            "Extract into variable",
+            CodeActionKind::REFACTOR_EXTRACT,
+            &uri,
+            extract_variable(properties, selection.start_byte, selection.end_byte),
+        ));
+    }
+
+    if let Some(node) = get_named_node_by_position(&properties.ast, params.range.start) {
+        if let Some(result) = inline_variable(properties, &node) {
+            let title = format!(
+                "Inline variable `{}`",
+                node.utf8_text(&properties.source_code).unwrap_or_default()
+            );
+            actions.push(to_code_action(
+                &title,
+                CodeActionKind::REFACTOR_INLINE,
+                &uri,
+                result,
+            ));
+        }
+    }
+
+    let result: CodeActionResponse = actions
+        .into_iter()
+        .filter(|action| disabled_support || action.disabled.is_none())
+        .filter(|action| match (&params.context.only, &action.kind) {
+            (Some(only), Some(kind)) => only
+                .iter()
+                .any(|requested| kind.as_str().starts_with(requested.as_str())),
+            _ => true,
+        })
+        .map(CodeActionOrCommand::CodeAction)
+        .collect();
+
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+fn to_code_action(
+    title: &str,
+    kind: CodeActionKind,
+    uri: &Url,
+    result: Result<Vec<TextEdit>, &str>,
+) -> CodeAction {
+    let (edit, disabled) = match result {
+        Ok(text_edits) => {
+            let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+            changes.insert(uri.clone(), text_edits);
+            (Some(WorkspaceEdit::new(changes)), None)
+        }
+        Err(reason) => (
+            None,
+            Some(CodeActionDisabled {
+                reason: reason.to_string(),
+            }),
+        ),
+    };
+
+    CodeAction {
+        title: title.to_string(),
+        kind: Some(kind),
+        diagnostics: None,
+        edit,
+        command: None,
+        is_preferred: None,
+        disabled,
+        data: None,
+    }
+}
+
+/// Declare the selected expression before the statement it's used in,
+/// then replace it and every identical expression after it with the new variable.
+fn extract_variable(
+    properties: &Properties,
+    start_byte: usize,
+    end_byte: usize,
+) -> Result<Vec<TextEdit>, &'static str> {
+    let source_code = &properties.source_code;
+
+    // surrounding whitespace is not part of the selection
+    let text = &source_code[start_byte..end_byte];
+    let start_byte = start_byte + text.iter().take_while(|c| c.is_ascii_whitespace()).count();
+    let end_byte = end_byte
+        - text
+            .iter()
+            .rev()
+            .take_while(|c| c.is_ascii_whitespace())
+            .count();
+    if start_byte >= end_byte {
+        return Err("The selection is not a complete expression");
+    }
+
+    let template = match get_declaration_template(&properties.language_id) {
+        Some(template) => template,
+        None => return Err("Extracting a variable is not supported for this language"),
+    };
+
+    let node = match properties
+        .ast
+        .root_node()
+        .named_descendant_for_byte_range(start_byte, end_byte)
+    {
+        Some(node) if node.start_byte() == start_byte && node.end_byte() == end_byte => node,
+        _ => return Err("The selection is not a complete expression"),
+    };
+    if !is_expression(&node) {
+        return Err("The selection is not a complete expression");
+    }
+
+    let statement = match get_enclosing_statement(&node) {
+        Some(statement) => statement,
+        None => return Err("The expression is not inside a block"),
+    };
+
+    // the declared type has to be spelled out where nothing infers it
+    let name = get_unused_name(properties, EXTRACTED_NAME, statement.start_byte());
+    let typed_name = if template.contains("$type") {
+        match infer_c_type(properties, &node) {
+            Some(declared_type) if declared_type.ends_with('*') => {
+                format!("{}{}", declared_type, name)
+            }
+            Some(declared_type) => format!("{} {}", declared_type, name),
+            None => return Err("The type of the expression can't be inferred"),
+        }
+    } else {
+        name.clone()
+    };
+    let value = node.utf8_text(source_code).unwrap_or_default();
+    let declaration = template
+        .replace("$type $name", &typed_name)
+        .replace("$name", &name)
+        .replace("$value", value);
+
+    // keep the statement on its own line with the same indentation
+    let indentation = get_indentation(source_code, statement.start_byte());
+    let declaration = match indentation {
+        Some(indentation) => format!("{}\n{}", declaration, indentation),
+        None => format!("{} ", declaration),
+    };
+
+    // the statement and the ones after it, in the same block
+    let mut occurrences: Vec<Node> = Vec::new();
+    let mut sibling = Some(statement);
+    while let Some(current) = sibling {
+        collect_identical_expressions(current, &node, source_code, &mut occurrences);
+        sibling = current.next_named_sibling();
+    }
+
+    let mut text_edits: Vec<TextEdit> = Vec::new();
+    let statement_start = ts_point_to_lsp_position(&statement.start_position());
+    if occurrences
+        .first()
+        .map(|first| first.start_byte() == statement.start_byte())
+        .unwrap_or(false)
+    {
+        // the statement is the expression itself, e.g. the tail of a rust block
+        let first = occurrences.remove(0);
+        text_edits.push(TextEdit {
+            range: lsp_types::Range {
+                start: statement_start,
+                end: ts_point_to_lsp_position(&first.end_position()),
+            },
+            new_text: format!("{}{}", declaration, name),
+        });
+    } else {
+        text_edits.push(TextEdit {
+            range: lsp_types::Range {
+                start: statement_start,
+                end: statement_start,
+            },
+            new_text: declaration,
+        });
+    }
+
+    for occurrence in occurrences {
+        text_edits.push(TextEdit {
+            range: lsp_types::Range {
+                start: ts_point_to_lsp_position(&occurrence.start_position()),
+                end: ts_point_to_lsp_position(&occurrence.end_position()),
+            },
+            new_text: name.clone(),
+        });
+    }
+
+    Ok(text_edits)
+}
+
+/// Replace every reference of the variable with its initializer and remove the declaration.
+/// Return None when the node is not a variable at all.
+fn inline_variable(
+    properties: &Properties,
+    node: &Node,
+) -> Option<Result<Vec<TextEdit>, &'static str>> {
+    let source_code = &properties.source_code;
+    let root_node = properties.ast.root_node();
+
+    let variable_name = node.utf8_text(source_code).ok()?;
+    let occurrences = find_definition(
+        &properties.definitions_lookup_map,
+        variable_name,
+        node,
+        &properties.ordered_scopes,
+    )?;
+    let definition = occurrences.first()?;
+    let definition_node = root_node.named_descendant_for_byte_range(
+        definition.location.start_byte,
+        definition.location.end_byte,
+    )?;
+
+    // parameters, loop variables and such
+    let (declarator, value) = get_initializer(&definition_node)?;
+    let statement = match get_enclosing_statement(&declarator) {
+        Some(statement) => statement,
+        None => return Some(Err("The variable is not declared inside a block")),
+    };
+
+    let write_ranges = build_write_ranges(source_code, &root_node, &properties.language_id);
+    if occurrences[1..].iter().any(|symbol| {
+        write_ranges
+            .iter()
+            .any(|range| range.start_byte == symbol.location.start_byte)
+    }) {
+        return Some(Err("The variable is assigned more than once"));
+    }
+    // e.g. `int a = 1, b = 2;`
+    let declared = write_ranges
+        .iter()
+        .filter(|range| {
+            statement.start_byte() <= range.start_byte
+                && range.end_byte <= statement.end_byte()
+                && !(value.start_byte() <= range.start_byte && range.end_byte <= value.end_byte())
+        })
+        .count();
+    if declared != 1 {
+        return Some(Err("The declaration declares more than one variable"));
+    }
+
+    let value_text = value.utf8_text(source_code).unwrap_or_default();
+    let mut text_edits: Vec<TextEdit> = Vec::new();
+
+    // remove the whole line when the declaration is alone on it
+    let (start_byte, end_byte) =
+        get_line_range(source_code, statement.start_byte(), statement.end_byte());
+    text_edits.push(TextEdit {
+        range: lsp_types::Range {
+            start: ts_point_to_lsp_position(&offset_to_position(source_code, start_byte)),
+            end: ts_point_to_lsp_position(&offset_to_position(source_code, end_byte)),
+        },
+        new_text: "".to_string(),
+    });
+
+    for symbol in &occurrences[1..] {
+        let reference = match root_node
+            .named_descendant_for_byte_range(symbol.location.start_byte, symbol.location.end_byte)
+        {
+            Some(reference) => reference,
+            None => continue,
+        };
+        let new_text = if needs_parentheses(&value, &reference) {
+            format!("({})", value_text)
+        } else {
+            value_text.to_string()
+        };
+        text_edits.push(TextEdit {
+            range: lsp_types::Range {
+                start: ts_point_to_lsp_position(&symbol.location.start_point),
+                end: ts_point_to_lsp_position(&symbol.location.end_point),
+            },
+            new_text,
+        });
+    }
+
+    Some(Ok(text_edits))
+}
+
+/// Get the template of a variable declaration, `$name` and `$value` are replaced, so is `$type`
+/// by the type of the value.
+fn get_declaration_template(language_id: &str) -> Option<&'static str> {
+    match language_id {
+        // there is no type inference before C23
+        "c" => Some("$type $name = $value;"),
+        "cpp" | "cuda" => Some("auto $name = $value;"),
+        "rust" => Some("let $name = $value;"),
+        "python" => Some("$name = $value"),
+        "javascript" | "typescript" => Some("const $name = $value;"),
+        "go" => Some("$name := $value"),
+        "kotlin" => Some("val $name = $value"),
+        _ => None,
+    }
+}
+
+/// Get the type of a C expression when it's evident, from literals, casts, comparisons and the
+/// declarations of the functions called in the document.
+fn infer_c_type(properties: &Properties, node: &Node) -> Option<String> {
+    let source_code = &properties.source_code;
+    let text = node.utf8_text(source_code).ok()?;
+    match node.kind() {
+        "number_literal" => {
+            let lowercase = text.to_lowercase();
+            if lowercase.starts_with("0x") {
+                return get_integer_type(&lowercase);
+            }
+            if lowercase.contains(['.', 'e']) {
+                let declared_type = if lowercase.ends_with('f') {
+                    "float"
+                } else {
+                    "double"
+                };
+                return Some(declared_type.to_string());
+            }
+            get_integer_type(&lowercase)
+        }
+        "char_literal" => Some("char".to_string()),
+        "string_literal" | "concatenated_string" => Some("const char *".to_string()),
+        "sizeof_expression" => Some("size_t".to_string()),
+        "cast_expression" => {
+            let declared_type = node.child_by_field_name("type")?;
+            Some(declared_type.utf8_text(source_code).ok()?.to_string())
+        }
+        "parenthesized_expression" => infer_c_type(properties, &node.named_child(0)?),
+        "unary_expression" => {
+            let operator = node.child_by_field_name("operator")?.kind();
+            match operator {
+                "!" => Some("int".to_string()),
+                "-" | "+" | "~" => infer_c_type(properties, &node.child_by_field_name("argument")?),
+                _ => None,
+            }
+        }
+        "binary_expression" => {
+            let operator = node.child_by_field_name("operator")?.kind();
+            if matches!(
+                operator,
+                "==" | "!=" | "<" | "<=" | ">" | ">=" | "&&" | "||"
+            ) {
+                return Some("int".to_string());
+            }
+            // e.g. pointer arithmetic or a promotion otherwise
+            let left = infer_c_type(properties, &node.child_by_field_name("left")?)?;
+            let right = infer_c_type(properties, &node.child_by_field_name("right")?)?;
+            (left == right && !left.ends_with('*')).then_some(left)
+        }
+        "identifier" => {
+            let definition = get_c_definition(properties, node)?;
+            get_c_declared_type(&definition, source_code, false)
+        }
+        "call_expression" => {
+            let function = node.child_by_field_name("function")?;
+            if function.kind() != "identifier" {
+                return None;
+            }
+            let definition = get_c_definition(properties, &function)?;
+            get_c_declared_type(&definition, source_code, true)
+        }
+        _ => None,
+    }
+}
+
+/// Get the name of the definition the identifier refers to.
+fn get_c_definition<'a>(properties: &'a Properties, identifier: &Node) -> Option<Node<'a>> {
+    let name = identifier.utf8_text(&properties.source_code).ok()?;
+    let definition = find_definition(
+        &properties.definitions_lookup_map,
+        name,
+        identifier,
+        &properties.ordered_scopes,
+    )?
+    .first()?;
+    properties.ast.root_node().named_descendant_for_byte_range(
+        definition.location.start_byte,
+        definition.location.end_byte,
+    )
+}
+
+/// Get the type of an integer literal from its suffix, e.g. `unsigned long` of `1ul`.
+fn get_integer_type(literal: &str) -> Option<String> {
+    let suffix: String = literal
+        .chars()
+        .rev()
+        .take_while(|c| *c == 'u' || *c == 'l')
+        .collect();
+    let unsigned = if suffix.contains('u') {
+        "unsigned "
+    } else {
+        ""
+    };
+    let size = match suffix.matches('l').count() {
+        0 => "int",
+        1 => "long",
+        2 => "long long",
+        _ => return None,
+    };
+    Some(format!("{}{}", unsigned, size))
+}
+
+/// Get the type the name is declared with, or the return type when it's a function, e.g.
+/// `const char *` of `const char *name(void)`. None if the type is too involved, e.g. arrays.
+fn get_c_declared_type(name: &Node, source_code: &[u8], is_function: bool) -> Option<String> {
+    let mut pointers = 0;
+    let mut is_declarator_of_function = false;
+    let mut current = name.parent()?;
+    loop {
+        match current.kind() {
+            "function_declarator" if is_function && !is_declarator_of_function => {
+                is_declarator_of_function = true
+            }
+            "pointer_declarator" if is_function == is_declarator_of_function => pointers += 1,
+            "init_declarator" if !is_function => {}
+            "declaration" | "function_definition" | "parameter_declaration" => break,
+            // e.g. function pointers and arrays
+            _ => return None,
+        }
+        current = current.parent()?;
+    }
+    if is_function != is_declarator_of_function {
+        return None;
+    }
+
+    let qualifiers = (0..current.named_child_count())
+        .filter_map(|i| current.named_child(i))
+        .filter(|child| child.kind() == "type_qualifier")
+        .filter_map(|child| child.utf8_text(source_code).ok());
+    let declared_type = current
+        .child_by_field_name("type")?
+        .utf8_text(source_code)
+        .ok()?;
+    let mut return_type = qualifiers
+        .chain(std::iter::once(declared_type))
+        .collect::<Vec<&str>>()
+        .join(" ");
+    if pointers > 0 {
+        return_type = format!("{} {}", return_type, "*".repeat(pointers));
+    }
+    Some(return_type)
+}
+
+/// Whether the node can be moved into a variable, identifiers are already one.
+fn is_expression(node: &Node) -> bool {
+    let kind = node.kind();
+    let is_expression_kind = kind.ends_with("expression")
+        || kind.ends_with("_literal")
+        || kind.ends_with("_operator")
+        || matches!(
+            kind,
+            "call" | "attribute" | "subscript" | "string" | "integer" | "float" | "list"
+        );
+
+    // the target of an assignment is not a value
+    let is_assigned = node
+        .parent()
+        .filter(|parent| parent.kind().contains("assignment"))
+        .and_then(|parent| parent.child_by_field_name("left"))
+        .map(|left| left.id() == node.id())
+        .unwrap_or(false);
+
+    is_expression_kind && !kind.ends_with("statement") && !is_assigned
+}
+
+/// Get the ancestor of the node which is a statement of a block.
+fn get_enclosing_statement<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut current = *node;
+    while let Some(parent) = current.parent() {
+        if BLOCKS.contains(&parent.kind()) {
+            return Some(current);
+        }
+        current = parent;
+    }
+    None
+}
+
+/// Get the declarator and the initializer of a variable definition,
+/// e.g. `x = 1` and `1` for the `x` in `int x = 1;`.
+fn get_initializer<'a>(definition: &Node<'a>) -> Option<(Node<'a>, Node<'a>)> {
+    let mut current = *definition;
+    // the name may be wrapped, e.g. by the expression_list of go
+    for _ in 0..2 {
+        let parent = current.parent()?;
+        if DECLARATORS.contains(&parent.kind()) {
+            let value = parent
+                .child_by_field_name("value")
+                .or_else(|| parent.child_by_field_name("right"))?;
+            if value.id() == current.id() {
+                return None;
+            }
+            // `x := 1` has a list of values
+            let value = if value.kind() == "expression_list" {
+                if value.named_child_count() != 1 {
+                    return None;
+                }
+                value.named_child(0)?
+            } else {
+                value
+            };
+            return Some((parent, value));
+        }
+        current = parent;
+    }
+    None
+}
+
+fn needs_parentheses(value: &Node, reference: &Node) -> bool {
+    if !COMPOUND_EXPRESSIONS.contains(&value.kind()) {
+        return false;
+    }
+    match reference.parent() {
+        Some(parent) => {
+            let kind = parent.kind();
+            (kind.ends_with("expression") || kind.ends_with("operator"))
+                && kind != "parenthesized_expression"
+        }
+        None => false,
+    }
+}
+
+fn collect_identical_expressions<'a>(
+    node: Node<'a>,
+    expression: &Node,
+    source_code: &[u8],
+    occurrences: &mut Vec<Node<'a>>,
+) {
+    if node.kind() == expression.kind()
+        && get_tokens(&node, source_code) == get_tokens(expression, source_code)
+        && is_expression(&node)
+    {
+        occurrences.push(node);
+        return;
+    }
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            collect_identical_expressions(child, expression, source_code, occurrences);
+        }
+    }
+}
+
+/// Get the text of the leaves, so that expressions are compared regardless of whitespace.
+fn get_tokens<'a>(node: &Node, source_code: &'a [u8]) -> Vec<&'a str> {
+    if node.child_count() == 0 {
+        return vec![node.utf8_text(source_code).unwrap_or_default()];
+    }
+    (0..node.child_count())
+        .filter_map(|i| node.child(i))
+        .flat_map(|child| get_tokens(&child, source_code))
+        .collect()
+}
+
+/// Get a name that is no keyword and is not bound in the document yet, nor visible from where it
+/// would be declared.
+fn get_unused_name(properties: &Properties, name: &str, offset: usize) -> String {
+    let visible = find_visible_definitions(
+        &properties.definitions_lookup_map,
+        offset,
+        &properties.ordered_scopes,
+        &properties.ast.root_node(),
+        &properties.language_id,
+    );
+    let is_used = |candidate: &str| {
+        properties
+            .keywords
+            .iter()
+            .any(|keyword| keyword == candidate)
+            || visible.iter().any(|(name, _)| *name == candidate)
+            || properties
+                .definitions_lookup_map
+                .keys()
+                .any(|key| key.rsplit_once(':').map(|(name, _)| name) == Some(candidate))
+    };
+
+    if !is_used(name) {
+        return name.to_string();
+    }
+    (1..)
+        .map(|i| format!("{}{}", name, i))
+        .find(|candidate| !is_used(candidate))
+        .unwrap()
+}
+
+/// Get the whitespace before the byte if it starts a line, None otherwise.
+fn get_indentation(source_code: &[u8], byte: usize) -> Option<String> {
+    let line_start = source_code[..byte]
+        .iter()
+        .rposition(|c| *c == b'\n')
+        .map_or(0, |i| i + 1);
+    let indentation = &source_code[line_start..byte];
+    if indentation.iter().all(|c| *c == b' ' || *c == b'\t') {
+        Some(String::from_utf8_lossy(indentation).to_string())
+    } else {
+        None
+    }
+}
+
+/// Extend the byte range to whole lines when nothing else is on them.
+fn get_line_range(source_code: &[u8], start_byte: usize, end_byte: usize) -> (usize, usize) {
+    let line_start = start_byte
+        - source_code[..start_byte]
+            .iter()
+            .rev()
+            .take_while(|c| **c == b' ' || **c == b'\t')
+            .count();
+    let line_end = end_byte
+        + source_code[end_byte..]
+            .iter()
+            .take_while(|c| **c == b' ' || **c == b'\t' || **c == b';')
+            .count();
+
+    let starts_line = line_start == 0 || source_code[line_start - 1] == b'\n';
+    let ends_line = line_end == source_code.len() || source_code[line_end] == b'\n';
+    if starts_line && ends_line {
+        (line_start, (line_end + 1).min(source_code.len()))
+    } else {
+        (start_byte, end_byte)
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use helper::{convert::ts_range_to_lsp_range, types::Symbol};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{CodeLens, CodeLensParams, Command, Location, SymbolKind, Url};
+use queries::locals::build_definition_ranges;
+use serde_json::json;
+use tree_sitter::Range;
+
+use crate::{caps::NO_OP_COMMAND, global_state::GlobalState, handler::references::find_references};
+
+/// Definitions that get a lens
+const LENS_KINDS: [SymbolKind; 7] = [
+    SymbolKind::FUNCTION,
+    SymbolKind::METHOD,
+    SymbolKind::CONSTRUCTOR,
+    SymbolKind::CLASS,
+    SymbolKind::STRUCT,
+    SymbolKind::INTERFACE,
+    SymbolKind::ENUM,
+];
+
+/// Put a lens on every function, method and type of the outline,
+/// the references are counted in codeLens/resolve.
+pub fn code_lens(id: RequestId, params: CodeLensParams, state: GlobalState) -> Response {
+    debug!("got code lens request #{}: {:?}", id, params);
+
+    let uri = params.text_document.uri;
+    let properties = if let Some(properties) = state.sources.get(&uri) {
+        properties
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No properties found for this document".to_string(),
+        );
+    };
+
+    let mut code_lenses: Vec<CodeLens> = Vec::new();
+    collect_code_lenses(&uri, &properties.document_symbols, &mut code_lenses);
+
+    let result = Some(code_lenses);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Count the references of the definition under the lens.
+pub fn code_lens_resolve(id: RequestId, mut params: CodeLens, state: GlobalState) -> Response {
+    let uri = params
+        .data
+        .as_ref()
+        .and_then(|data| data.get("uri"))
+        .and_then(|uri| serde_json::from_value::<Url>(uri.clone()).ok());
+    let uri = if let Some(uri) = uri {
+        uri
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No document found for this code lens".to_string(),
+        );
+    };
+    let position = params.range.start;
+
+    // the document may have changed since the lens was created
+    let locations = find_references(&uri, position, &state).unwrap_or_default();
+    // definitions are no references of themselves, e.g. a prototype and the function
+    let count = locations.len() - count_definitions(&locations, &state);
+    let title = match count {
+        1 => "1 reference".to_string(),
+        count => format!("{} references", count),
+    };
+
+    // without a command of the client, running the lens does nothing
+    params.command = Some(match &state.config.code_lens.show_references_command {
+        Some(command) => Command {
+            title,
+            command: command.clone(),
+            arguments: Some(vec![
+                json!(uri),
+                json!(position),
+                serde_json::to_value(&locations).unwrap(),
+            ]),
+        },
+        None => Command {
+            title,
+            command: NO_OP_COMMAND.to_string(),
+            arguments: None,
+        },
+    });
+
+    let result = serde_json::to_value(&params).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Count the locations which are the name of a definition.
+fn count_definitions(locations: &[Location], state: &GlobalState) -> usize {
+    let mut definitions: HashMap<&Url, Vec<Range>> = HashMap::new();
+    locations
+        .iter()
+        .filter(|location| {
+            let ranges = definitions.entry(&location.uri).or_insert_with(|| {
+                state
+                    .get_file(&location.uri)
+                    .map(|(tree, source_code, language_id)| {
+                        build_definition_ranges(source_code, &tree.root_node(), language_id)
+                    })
+                    .unwrap_or_default()
+            });
+            ranges
+                .iter()
+                .any(|range| ts_range_to_lsp_range(range) == location.range)
+        })
+        .count()
+}
+
+fn collect_code_lenses(uri: &Url, symbols: &[Symbol], code_lenses: &mut Vec<CodeLens>) {
+    for symbol in symbols {
+        if symbol
+            .symbol_kind
+            .iter()
+            .any(|kind| LENS_KINDS.contains(kind))
+        {
+            code_lenses.push(CodeLens {
+                range: ts_range_to_lsp_range(&symbol.location),
+                command: None,
+                data: Some(json!({ "uri": uri })),
+            });
+        }
+        if let Some(children) = &symbol.children {
+            collect_code_lenses(uri, children, code_lenses);
+        }
+    }
+}
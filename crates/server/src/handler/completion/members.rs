@@ -0,0 +1,375 @@
+use std::collections::HashSet;
+
+use helper::{convert::lsp_range_to_ts_range, types::Symbol};
+use lsp_types::{CompletionItem, CompletionItemKind, Position, SymbolKind, Url};
+use queries::{
+    children::build_document_symbols, implementations::build_methods, locals::find_definition,
+};
+use tree_sitter::{Node, Tree};
+
+use crate::{
+    global_state::GlobalState,
+    handler::goto_type_definition::{get_declared_type, get_type_identifier},
+};
+
+/// Operators accessing a member of the expression before them
+const MEMBER_OPERATORS: [&str; 3] = [".", "->", "::"];
+
+/// Kinds of the outline symbols whose children are members, typedefs of anonymous structs included
+const CONTAINER_KINDS: [SymbolKind; 7] = [
+    SymbolKind::STRUCT,
+    SymbolKind::CLASS,
+    SymbolKind::INTERFACE,
+    SymbolKind::ENUM,
+    SymbolKind::OBJECT,
+    SymbolKind::MODULE,
+    SymbolKind::TYPE_PARAMETER,
+];
+
+/// Fields of the expressions accessing a member, as (object, member), e.g. `value` and `field` of
+/// `a.b` in rust
+const MEMBER_FIELDS: [(&str, &str); 5] = [
+    ("argument", "field"),
+    ("value", "field"),
+    ("operand", "field"),
+    ("object", "property"),
+    ("object", "attribute"),
+];
+
+/// Fields holding the value a binding is initialized with
+const VALUE_FIELDS: [&str; 2] = ["value", "right"];
+
+/// Fields holding the type a value is constructed from, e.g. `function` of `Foo()` in python
+const CONSTRUCTOR_FIELDS: [&str; 4] = ["constructor", "function", "name", "type"];
+
+/// How far the value may be from the name, e.g. `a, b := A{}, B{}` nests it in two lists
+const MAX_VALUE_DEPTH: usize = 3;
+
+/// How many members may be accessed before the position, e.g. two for `a.b.c.`
+const MAX_MEMBER_DEPTH: usize = 5;
+
+/// A member of a type and the name of the type it's declared with, if any.
+struct Member {
+    symbol: Symbol,
+    type_name: Option<String>,
+}
+
+/// Complete the fields and methods of the receiver before `.`, `->` or `::` at the position.
+/// Return None if the position is not after a member access.
+pub(super) fn member_completion(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Option<Vec<CompletionItem>> {
+    let properties = state.sources.get(uri)?;
+    let source_code = &properties.source_code;
+    let tree = &properties.ast;
+
+    let point = lsp_types::Range::new(position, position);
+    let offset = lsp_range_to_ts_range(&point, source_code).start_byte;
+    let operator_end = source_code[..offset]
+        .iter()
+        .rposition(|c| !c.is_ascii_alphanumeric() && *c != b'_')
+        .map_or(0, |index| index + 1);
+    let operator = MEMBER_OPERATORS
+        .iter()
+        .find(|operator| source_code[..operator_end].ends_with(operator.as_bytes()))?;
+    let receiver_end = operator_end - operator.len();
+    if receiver_end == 0 {
+        return Some(vec![]);
+    }
+
+    let receiver = tree
+        .root_node()
+        .named_descendant_for_byte_range(receiver_end - 1, receiver_end)?;
+    // e.g. `1.`, a number is no receiver
+    if receiver.kind().contains("number")
+        || receiver.kind().contains("float")
+        || receiver.kind().contains("integer")
+    {
+        return Some(vec![]);
+    }
+
+    // `Type::` accesses the members of the type itself
+    let type_name = if *operator == "::" {
+        receiver.utf8_text(source_code).ok().map(str::to_string)
+    } else {
+        get_receiver_type(uri, &receiver, state, MAX_MEMBER_DEPTH)
+    };
+    let members = type_name
+        .map(|type_name| get_type_members(state, &properties.language_id, &type_name))
+        .unwrap_or_default();
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let items: Vec<CompletionItem> = if members.is_empty() {
+        get_field_names(source_code, tree, &properties.language_id, operator_end)
+            .into_iter()
+            .filter(|name| seen.insert(name.clone()))
+            .map(|name| CompletionItem {
+                label: name,
+                kind: Some(CompletionItemKind::FIELD),
+                ..Default::default()
+            })
+            .collect()
+    } else {
+        members
+            .into_iter()
+            .filter(|member| seen.insert(member.symbol.name.clone()))
+            .map(|member| CompletionItem {
+                label: member.symbol.name,
+                kind: member.symbol.completion_kind.last().copied(),
+                detail: member.symbol.detail,
+                ..Default::default()
+            })
+            .collect()
+    };
+    Some(items)
+}
+
+/// Get the name of the type of the receiver of a member access, e.g. `a` of `a.f()`, None if it
+/// can't be inferred.
+pub(crate) fn infer_receiver_type(
+    uri: &Url,
+    receiver: &Node,
+    state: &GlobalState,
+) -> Option<String> {
+    get_receiver_type(uri, receiver, state, MAX_MEMBER_DEPTH)
+}
+
+/// Get the name of the type of the receiver, from the definition of the receiver or of the
+/// member it is.
+fn get_receiver_type(
+    uri: &Url,
+    receiver: &Node,
+    state: &GlobalState,
+    depth: usize,
+) -> Option<String> {
+    let properties = state.sources.get(uri)?;
+    let source_code = &properties.source_code;
+    let name = receiver.utf8_text(source_code).ok()?;
+
+    if ["self", "this"].contains(&name) {
+        return get_enclosing_type(receiver, source_code);
+    }
+
+    // `a.b.`, the type of `b` is declared in the type of `a`
+    if let Some(parent) = receiver.parent() {
+        for (object_field, member_field) in MEMBER_FIELDS {
+            let is_member = parent
+                .child_by_field_name(member_field)
+                .is_some_and(|member| member.id() == receiver.id());
+            if !is_member || depth == 0 {
+                continue;
+            }
+            let object = parent.child_by_field_name(object_field)?;
+            let object_type = get_receiver_type(uri, &object, state, depth - 1)?;
+            return get_type_members(state, &properties.language_id, &object_type)
+                .into_iter()
+                .find(|member| member.symbol.name == name)
+                .and_then(|member| member.type_name);
+        }
+    }
+
+    let definition = find_definition(
+        &properties.definitions_lookup_map,
+        name,
+        receiver,
+        &properties.ordered_scopes,
+    )?
+    .first()?;
+    let definition = properties.ast.root_node().named_descendant_for_byte_range(
+        definition.location.start_byte,
+        definition.location.end_byte,
+    )?;
+    let type_node = get_declared_type(&definition)
+        .and_then(|declared_type| get_type_identifier(&declared_type))
+        .or_else(|| get_constructed_type(&definition))?;
+    type_node.utf8_text(source_code).ok().map(str::to_string)
+}
+
+/// Get the name of the class or impl the node is in, the type of `self` and `this`.
+fn get_enclosing_type(node: &Node, source_code: &[u8]) -> Option<String> {
+    let mut current = node.parent();
+    while let Some(node) = current {
+        let type_node = if node.kind() == "impl_item" {
+            node.child_by_field_name("type")
+                .and_then(|impl_type| get_type_identifier(&impl_type))
+        } else if node.kind().contains("class") || node.kind() == "struct_specifier" {
+            node.child_by_field_name("name").or_else(|| {
+                let mut cursor = node.walk();
+                let name = node
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() == "type_identifier");
+                name
+            })
+        } else {
+            None
+        };
+        if let Some(type_node) = type_node {
+            return type_node.utf8_text(source_code).ok().map(str::to_string);
+        }
+        current = node.parent();
+    }
+    None
+}
+
+/// Get the type the binding is initialized with a new value of, e.g. `Foo` of `x = Foo()`.
+fn get_constructed_type<'a>(definition: &Node<'a>) -> Option<Node<'a>> {
+    let mut current = definition.parent();
+    for _ in 0..MAX_VALUE_DEPTH {
+        let node = current?;
+        if let Some(value) = VALUE_FIELDS
+            .iter()
+            .find_map(|field| node.child_by_field_name(field))
+        {
+            // the first value of a list, e.g. `a, b = A(), B()`
+            let value = if value.kind().contains("list") {
+                value.named_child(0)?
+            } else {
+                value
+            };
+            let constructor = CONSTRUCTOR_FIELDS
+                .iter()
+                .find_map(|field| value.child_by_field_name(field))?;
+            // `Foo::new()`
+            let constructor = constructor
+                .child_by_field_name("path")
+                .unwrap_or(constructor);
+            return match constructor.kind() {
+                "identifier" | "type_identifier" => Some(constructor),
+                _ => get_type_identifier(&constructor),
+            };
+        }
+        current = node.parent();
+    }
+    None
+}
+
+/// Get the members of every type with the name in the files of the language, and the methods
+/// declared apart from them, e.g. in an impl or with a receiver.
+fn get_type_members(state: &GlobalState, language_id: &str, type_name: &str) -> Vec<Member> {
+    let mut members: Vec<Member> = Vec::new();
+    for (uri, tree, source_code, file_language_id) in state.files_mentioning(type_name, language_id)
+    {
+        // the outline is only built for opened documents and the files the index says define the
+        // type, the methods may be declared in any file, e.g. with a receiver in go
+        let is_opened = state
+            .sources
+            .get(uri)
+            .is_some_and(|properties| !properties.language_id.is_empty());
+        let defines_type = match state.workspace_index.files.get(uri) {
+            Some(file) if !is_opened => file.symbols.iter().any(|symbol| {
+                symbol.name == type_name && symbol.symbol_kind.contains(&SymbolKind::CLASS)
+            }),
+            _ => true,
+        };
+        let mut symbols = if defines_type {
+            build_document_symbols(source_code, &tree.root_node(), file_language_id)
+        } else {
+            vec![]
+        };
+        while let Some(symbol) = symbols.pop() {
+            let children = symbol.children.clone().unwrap_or_default();
+            if symbol.name == type_name
+                && symbol
+                    .symbol_kind
+                    .iter()
+                    .any(|kind| CONTAINER_KINDS.contains(kind))
+            {
+                // e.g. attributes assigned in the methods of a python class
+                let attributes = children
+                    .iter()
+                    .filter(|child| child.symbol_kind.contains(&SymbolKind::METHOD))
+                    .flat_map(|method| method.children.clone().unwrap_or_default())
+                    .filter(|child| child.symbol_kind.contains(&SymbolKind::FIELD));
+                members.extend(
+                    children
+                        .iter()
+                        .cloned()
+                        .chain(attributes)
+                        .map(|child| Member {
+                            type_name: get_member_type(tree, source_code, &child),
+                            symbol: child,
+                        }),
+                );
+            }
+            symbols.extend(children);
+        }
+
+        for method in build_methods(source_code, &tree.root_node(), file_language_id) {
+            if method.owner != type_name {
+                continue;
+            }
+            members.push(Member {
+                symbol: Symbol {
+                    name: method.name,
+                    completion_kind: vec![CompletionItemKind::METHOD],
+                    symbol_kind: vec![SymbolKind::METHOD],
+                    location: method.range,
+                    ..Symbol::default()
+                },
+                type_name: None,
+            });
+        }
+    }
+    members
+}
+
+/// Get the name of the type a member is declared with.
+fn get_member_type(tree: &Tree, source_code: &[u8], member: &Symbol) -> Option<String> {
+    let name_node = tree
+        .root_node()
+        .named_descendant_for_byte_range(member.location.start_byte, member.location.end_byte)?;
+    let type_node = get_declared_type(&name_node)
+        .and_then(|declared_type| get_type_identifier(&declared_type))?;
+    type_node.utf8_text(source_code).ok().map(str::to_string)
+}
+
+/// Get the names of all fields of the file, declared or accessed, for receivers of unknown type.
+/// The name being typed after the operator is not one of them, nor what follows the operator
+/// while the access is incomplete, e.g. `return` of `p.\n return 0;`.
+fn get_field_names(
+    source_code: &Vec<u8>,
+    tree: &Tree,
+    language_id: &str,
+    operator_end: usize,
+) -> Vec<String> {
+    let mut names: Vec<String> = Vec::new();
+
+    let mut symbols = build_document_symbols(source_code, &tree.root_node(), language_id);
+    while let Some(symbol) = symbols.pop() {
+        if symbol.symbol_kind.contains(&SymbolKind::FIELD) {
+            names.push(symbol.name.clone());
+        }
+        symbols.extend(symbol.children.unwrap_or_default());
+    }
+
+    let mut cursor = tree.walk();
+    let mut visited_children = false;
+    loop {
+        let node = cursor.node();
+        if !visited_children
+            && ["field_identifier", "property_identifier"].contains(&node.kind())
+            && !source_code
+                .get(operator_end..node.start_byte())
+                .is_some_and(|gap| gap.iter().all(u8::is_ascii_whitespace))
+        {
+            if let Ok(name) = node.utf8_text(source_code) {
+                names.push(name.to_string());
+            }
+        }
+        if !visited_children && cursor.goto_first_child() {
+            continue;
+        }
+        if cursor.goto_next_sibling() {
+            visited_children = false;
+        } else if cursor.goto_parent() {
+            visited_children = true;
+        } else {
+            break;
+        }
+    }
+
+    names.sort();
+    names
+}
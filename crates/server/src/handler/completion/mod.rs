@@ -0,0 +1,389 @@
+use std::collections::{HashMap, HashSet};
+
+use helper::{convert::lsp_range_to_ts_range, fuzzy::fuzzy_match, types::Symbol};
+use log::debug;
+use lsp_server::{ErrorCode::InternalError, RequestId, Response};
+use lsp_types::{
+    CompletionItem, CompletionItemKind, CompletionList, CompletionParams, CompletionResponse,
+    CompletionTextEdit, CompletionTriggerKind, Documentation, Position, Range, TextEdit, Url,
+};
+use queries::{
+    children::{get_documentation, get_signature},
+    locals::{find_definition_by_offset, find_visible_definitions},
+    utils::get_scope_chain_by_offset,
+};
+use serde_json::json;
+use tree_sitter::Node;
+
+use crate::global_state::GlobalState;
+
+pub(super) mod members;
+mod paths;
+mod snippets;
+mod workspace;
+
+/// Most items sent at once, the client asks again for more as the word being typed grows
+const MAX_ITEMS: usize = 100;
+
+/// Scopes deeper than this rank the same
+const MAX_DEPTH_BONUS: usize = 5;
+
+/// Definitions of other files rank below those of the document, unless they match much better
+const WORKSPACE_PENALTY: i64 = 10;
+
+/// How far the statement declaring a binding may be from its name, e.g. `int *a = 0` nests it in
+/// a pointer declarator and an init declarator
+const MAX_DECLARATION_DEPTH: usize = 4;
+
+/// A completion item and how much it's preferred over others matching as well, e.g. for the scope
+/// of its binding being closer to the position.
+struct Candidate {
+    item: CompletionItem,
+    bonus: i64,
+}
+
+pub fn completion(id: RequestId, params: CompletionParams, state: GlobalState) -> Response {
+    debug!("got completion request #{}: {:?}", id, params);
+
+    let uri = &params.text_document_position.text_document.uri;
+    let position = params.text_document_position.position;
+    let properties = if let Some(properties) = state.sources.get(uri) {
+        properties
+    } else {
+        return Response::new_err(
+            id,
+            InternalError as i32,
+            "No properties found for this document".to_string(),
+        );
+    };
+
+    // the word being typed is what candidates are matched against, and what they replace, in a
+    // path only the segment being typed
+    let path_candidates = paths::path_completion(uri, position, &state);
+    let (prefix, range) = match &path_candidates {
+        Some((_, prefix, range)) => (prefix.clone(), *range),
+        None => get_word_before(&properties.source_code, position),
+    };
+
+    let trigger_kind = params
+        .context
+        .map(|context| context.trigger_kind)
+        .unwrap_or(CompletionTriggerKind::INVOKED);
+
+    // after `.`, `->` or `::` only members make sense, however the completion is triggered
+    let candidates: Vec<Candidate> = if let Some((items, _, _)) = path_candidates {
+        items
+            .into_iter()
+            .map(|item| Candidate { item, bonus: 0 })
+            .collect()
+    } else if let Some(items) = members::member_completion(uri, position, &state) {
+        items
+            .into_iter()
+            .map(|item| Candidate {
+                item: with_data(item, uri, position),
+                bonus: 0,
+            })
+            .collect()
+    } else if trigger_kind == CompletionTriggerKind::TRIGGER_CHARACTER {
+        // e.g. `>` of a comparison, which is no member access, or `/` of a division
+        vec![]
+    } else {
+        let mut candidates: Vec<Candidate> = properties
+            .keywords
+            .iter()
+            .map(|keyword| Candidate {
+                item: CompletionItem {
+                    label: keyword.to_string(),
+                    kind: Some(CompletionItemKind::KEYWORD),
+                    ..Default::default()
+                },
+                bonus: 0,
+            })
+            .collect();
+
+        let word_start = lsp_range_to_ts_range(&range, &properties.source_code).start_byte;
+        let chain = get_scope_chain_by_offset(word_start, &properties.ordered_scopes);
+        debug!("scope chain: {:?}", chain);
+
+        // names as highlighted in the visible scopes, the innermost first
+        let mut kinds: HashMap<&str, CompletionItemKind> = HashMap::new();
+        let identifiers: Vec<&Symbol> = chain
+            .iter()
+            .filter_map(|scope_id| properties.identifiers.get(scope_id))
+            .flatten()
+            .collect();
+        for symbol in &identifiers {
+            kinds
+                .entry(symbol.name.as_str())
+                .or_insert(*symbol.completion_kind.last().unwrap());
+        }
+
+        for (name, depth) in find_visible_definitions(
+            &properties.definitions_lookup_map,
+            word_start,
+            &properties.ordered_scopes,
+            &properties.ast.root_node(),
+            &properties.language_id,
+        ) {
+            candidates.push(Candidate {
+                item: with_data(
+                    CompletionItem {
+                        label: name.to_string(),
+                        kind: Some(
+                            kinds
+                                .get(name)
+                                .copied()
+                                .unwrap_or(CompletionItemKind::VARIABLE),
+                        ),
+                        ..Default::default()
+                    },
+                    uri,
+                    position,
+                ),
+                bonus: depth.min(MAX_DEPTH_BONUS) as i64,
+            });
+        }
+
+        // names bound nowhere in the document, e.g. functions declared in included headers
+        let bindings: HashSet<&str> = properties
+            .definitions_lookup_map
+            .keys()
+            .filter_map(|key| key.rsplit_once(':'))
+            .map(|(name, _)| name)
+            .collect();
+        for symbol in identifiers {
+            let kind = *symbol.completion_kind.last().unwrap();
+            // the word being typed is an identifier too, and numbers are no identifiers
+            if kind == CompletionItemKind::OPERATOR
+                || !symbol
+                    .name
+                    .starts_with(|c: char| c.is_alphabetic() || c == '_')
+                || symbol.location.start_byte == word_start
+                || bindings.contains(symbol.name.as_str())
+            {
+                continue;
+            }
+            candidates.push(Candidate {
+                item: with_data(
+                    CompletionItem {
+                        label: symbol.name.clone(),
+                        kind: Some(kind),
+                        ..Default::default()
+                    },
+                    uri,
+                    position,
+                ),
+                bonus: 0,
+            });
+        }
+
+        candidates.extend(
+            workspace::workspace_completion(uri, &state)
+                .into_iter()
+                .map(|item| Candidate {
+                    item,
+                    bonus: -WORKSPACE_PENALTY,
+                }),
+        );
+
+        candidates.extend(
+            snippets::snippet_completion(uri, position, &state)
+                .into_iter()
+                .map(|item| Candidate { item, bonus: 0 }),
+        );
+        candidates
+    };
+
+    let (items, is_incomplete) = rank(candidates, &prefix, range);
+
+    let result = Some(CompletionResponse::List(CompletionList {
+        is_incomplete,
+        items,
+    }));
+    let result = serde_json::to_value(&result).unwrap();
+    let resp = lsp_server::Response {
+        id,
+        result: Some(result),
+        error: None,
+    };
+    debug!("send completion response {:?}", resp);
+    resp
+}
+
+/// Fill in the signature and the documentation of the definition the item completes.
+pub fn completion_resolve(id: RequestId, mut item: CompletionItem, state: GlobalState) -> Response {
+    let data = item.data.as_ref();
+    let uri = data
+        .and_then(|data| data.get("uri"))
+        .and_then(|uri| serde_json::from_value::<Url>(uri.clone()).ok());
+    let position = data
+        .and_then(|data| data.get("position"))
+        .and_then(|position| serde_json::from_value::<Position>(position.clone()).ok());
+
+    if let (Some(properties), Some(position)) =
+        (uri.and_then(|uri| state.sources.get(&uri)), position)
+    {
+        let source_code = &properties.source_code;
+        let root_node = properties.ast.root_node();
+
+        // definitions of the outline, otherwise the local binding visible from the position
+        let mut symbols: Vec<&Symbol> = properties.document_symbols.iter().collect();
+        let mut outline_symbol = None;
+        while let Some(symbol) = symbols.pop() {
+            if symbol.name == item.label {
+                outline_symbol = Some(symbol);
+                break;
+            }
+            symbols.extend(symbol.children.iter().flatten());
+        }
+        let definition: Option<Node> = match outline_symbol {
+            Some(symbol) => {
+                let range = symbol.definition_range.unwrap_or(symbol.location);
+                root_node.descendant_for_byte_range(range.start_byte, range.end_byte)
+            }
+            None => find_definition_by_offset(
+                &properties.definitions_lookup_map,
+                &item.label,
+                lsp_range_to_ts_range(&Range::new(position, position), source_code).start_byte,
+                &properties.ordered_scopes,
+            )
+            .and_then(|occurrences| occurrences.first())
+            .and_then(|definition| {
+                root_node.named_descendant_for_byte_range(
+                    definition.location.start_byte,
+                    definition.location.end_byte,
+                )
+            })
+            .map(|name_node| get_declaration(&name_node)),
+        };
+
+        if let Some(definition) = definition {
+            item.detail = Some(get_signature(source_code, &definition));
+            item.documentation =
+                get_documentation(source_code, &definition).map(Documentation::String);
+        }
+    }
+
+    let result = serde_json::to_value(&item).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Get the identifier before the position and its range, empty at the start of a word.
+fn get_word_before(source_code: &[u8], position: Position) -> (String, Range) {
+    let point = Range::new(position, position);
+    let offset = lsp_range_to_ts_range(&point, source_code).start_byte;
+    let start = source_code[..offset]
+        .iter()
+        .rposition(|c| !c.is_ascii_alphanumeric() && *c != b'_')
+        .map_or(0, |index| index + 1);
+
+    let prefix = String::from_utf8_lossy(&source_code[start..offset]).to_string();
+    let start = Position::new(
+        position.line,
+        position.character.saturating_sub((offset - start) as u32),
+    );
+    (prefix, Range::new(start, position))
+}
+
+/// Keep the items matching the prefix, best first, with the range they replace.
+/// Return whether some were left out.
+fn rank(candidates: Vec<Candidate>, prefix: &str, range: Range) -> (Vec<CompletionItem>, bool) {
+    let mut scored: Vec<(i64, Candidate)> = candidates
+        .into_iter()
+        .filter_map(|candidate| {
+            let filter_text = candidate
+                .item
+                .filter_text
+                .as_ref()
+                .unwrap_or(&candidate.item.label);
+            let score = if prefix.is_empty() {
+                0
+            } else {
+                fuzzy_match(prefix, filter_text)?
+            };
+            let bonus = get_kind_bonus(candidate.item.kind) + candidate.bonus;
+            Some((score + bonus, candidate))
+        })
+        .collect();
+    scored.sort_by(|(score, candidate), (other_score, other)| {
+        other_score
+            .cmp(score)
+            .then_with(|| candidate.item.label.cmp(&other.item.label))
+    });
+
+    // the best of the candidates with the same label
+    let mut labels: HashSet<String> = HashSet::new();
+    scored.retain(|(_, candidate)| labels.insert(candidate.item.label.clone()));
+
+    let is_incomplete = scored.len() > MAX_ITEMS;
+    scored.truncate(MAX_ITEMS);
+
+    let items = scored
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, candidate))| {
+            let mut item = candidate.item;
+            let new_text = item
+                .insert_text
+                .take()
+                .unwrap_or_else(|| item.label.clone());
+            item.sort_text = Some(format!("{:04}", index));
+            item.filter_text.get_or_insert_with(|| item.label.clone());
+            item.text_edit = Some(CompletionTextEdit::Edit(TextEdit { range, new_text }));
+            item
+        })
+        .collect();
+    (items, is_incomplete)
+}
+
+/// Bindings first, then definitions of functions and types, then keywords and snippets.
+fn get_kind_bonus(kind: Option<CompletionItemKind>) -> i64 {
+    match kind {
+        Some(
+            CompletionItemKind::VARIABLE
+            | CompletionItemKind::FIELD
+            | CompletionItemKind::PROPERTY
+            | CompletionItemKind::ENUM_MEMBER
+            | CompletionItemKind::CONSTANT
+            | CompletionItemKind::VALUE,
+        ) => 3,
+        Some(
+            CompletionItemKind::FUNCTION
+            | CompletionItemKind::METHOD
+            | CompletionItemKind::CLASS
+            | CompletionItemKind::STRUCT
+            | CompletionItemKind::INTERFACE
+            | CompletionItemKind::ENUM
+            | CompletionItemKind::MODULE
+            | CompletionItemKind::TYPE_PARAMETER,
+        ) => 2,
+        Some(CompletionItemKind::KEYWORD) => 1,
+        _ => 0,
+    }
+}
+
+/// Remember where the item was asked for, to resolve it later.
+fn with_data(mut item: CompletionItem, uri: &Url, position: Position) -> CompletionItem {
+    item.data = Some(json!({ "uri": uri, "position": position }));
+    item
+}
+
+/// Get the statement declaring the name, e.g. `int a = 0;` of `a`.
+fn get_declaration<'a>(name_node: &Node<'a>) -> Node<'a> {
+    let mut current = *name_node;
+    for _ in 0..MAX_DECLARATION_DEPTH {
+        let kind = current.kind();
+        if kind.contains("declaration")
+            || kind.contains("definition")
+            || kind.contains("assignment")
+            || kind.contains("parameter")
+        {
+            return current;
+        }
+        current = match current.parent() {
+            Some(parent) => parent,
+            None => break,
+        };
+    }
+    *name_node
+}
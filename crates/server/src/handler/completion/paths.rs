@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use helper::convert::lsp_range_to_ts_range;
+use lsp_types::{CompletionItem, CompletionItemKind, Position, Range, Url};
+use queries::links::build_links;
+
+use crate::{
+    global_state::GlobalState,
+    handler::specifiers::{resolve_specifier, SCRIPT_EXTENSIONS},
+};
+
+/// Complete the files and directories of the include or import the position is in, relative to
+/// the document and to the include roots. Return the items, the part of the path segment before
+/// the position and the range of the whole segment, or None if the position is in no include or
+/// import.
+pub(super) fn path_completion(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Option<(Vec<CompletionItem>, String, Range)> {
+    let properties = state.sources.get(uri)?;
+    let source_code = &properties.source_code;
+    let language_id = properties.language_id.as_str();
+
+    let point = Range::new(position, position);
+    let offset = lsp_range_to_ts_range(&point, source_code).start_byte;
+    let link = build_links(source_code, &properties.ast.root_node(), language_id)
+        .into_iter()
+        .find(|link| link.range.start_byte <= offset && offset <= link.range.end_byte)?;
+
+    // e.g. `import a.` captures the next line too while the statement is incomplete
+    let typed = String::from_utf8_lossy(&source_code[link.range.start_byte..offset]).to_string();
+    if typed.contains('\n') {
+        return None;
+    }
+    let rest = String::from_utf8_lossy(&source_code[offset..link.range.end_byte]).to_string();
+    let rest = rest.lines().next().unwrap_or_default();
+
+    let separator = match (link.kind.as_str(), language_id) {
+        ("path" | "system", _) => "/",
+        ("module", "hare") => "::",
+        ("module", _) => ".",
+        // Go packages are no paths of the workspace
+        _ => return Some((vec![], String::new(), point)),
+    };
+    let (parent, prefix) = match typed.rfind(separator) {
+        Some(index) => typed.split_at(index + separator.len()),
+        None => ("", typed.as_str()),
+    };
+    let suffix = match rest.find(separator) {
+        Some(index) => &rest[..index],
+        None => rest,
+    };
+    let range = Range::new(
+        Position::new(
+            position.line,
+            position.character.saturating_sub(prefix.len() as u32),
+        ),
+        Position::new(position.line, position.character + suffix.len() as u32),
+    );
+
+    let directory = uri
+        .to_file_path()
+        .ok()
+        .and_then(|path| path.parent().map(Path::to_path_buf))?;
+    let items = get_directories(state, &directory, &link.kind, language_id, parent)
+        .iter()
+        .flat_map(|directory| fs::read_dir(directory).into_iter().flatten())
+        .filter_map(|entry| {
+            let entry = entry.ok()?;
+            let name = entry.file_name().to_string_lossy().to_string();
+            // hidden files, unless asked for
+            if name.starts_with('.') && !prefix.starts_with('.') {
+                return None;
+            }
+            let is_dir = entry.path().is_dir();
+            let label = get_label(&name, is_dir, &link.kind, language_id)?;
+            Some(CompletionItem {
+                label,
+                kind: Some(if is_dir {
+                    CompletionItemKind::FOLDER
+                } else {
+                    CompletionItemKind::FILE
+                }),
+                detail: Some(entry.path().display().to_string()),
+                ..Default::default()
+            })
+        })
+        .collect();
+
+    Some((items, prefix.to_string(), range))
+}
+
+/// Get the directories the segment being typed may name an entry of, `parent` being the segments
+/// before it and their separators, e.g. `a/` of `a/b`.
+fn get_directories(
+    state: &GlobalState,
+    directory: &Path,
+    kind: &str,
+    language_id: &str,
+    parent: &str,
+) -> Vec<PathBuf> {
+    resolve_specifier(state, directory, kind, language_id, parent)
+        .into_iter()
+        .filter(|directory| directory.is_dir())
+        .collect()
+}
+
+/// Get the name an entry is completed as, None if it can't be included or imported.
+fn get_label(name: &str, is_dir: bool, kind: &str, language_id: &str) -> Option<String> {
+    let is_identifier =
+        |name: &str| !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    match (kind, language_id) {
+        // packages and modules, without `__init__.py` and `__pycache__`
+        ("module", "python") => {
+            let name = if is_dir {
+                name
+            } else {
+                name.strip_suffix(".py")?
+            };
+            (is_identifier(name) && !name.starts_with("__")).then(|| name.to_string())
+        }
+        ("module", _) => (is_dir && is_identifier(name)).then(|| name.to_string()),
+        // specifiers usually omit the extension
+        ("path", "javascript" | "typescript") if !is_dir => Some(
+            SCRIPT_EXTENSIONS
+                .iter()
+                .find_map(|extension| name.strip_suffix(&format!(".{}", extension)))
+                .unwrap_or(name)
+                .to_string(),
+        ),
+        _ => Some(name.to_string()),
+    }
+}
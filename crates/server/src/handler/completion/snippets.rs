@@ -0,0 +1,103 @@
+use std::collections::BTreeMap;
+
+use helper::convert::lsp_range_to_ts_range;
+use log::error;
+use lsp_types::{CompletionItem, CompletionItemKind, InsertTextFormat, Position, Url};
+use queries::utils::get_snippets_source;
+use serde::Deserialize;
+
+use crate::global_state::GlobalState;
+
+/// A snippet of the `snippets.json` of a language, in the format of VS Code snippets
+#[derive(Debug, Deserialize)]
+struct Snippet {
+    prefix: String,
+    body: SnippetBody,
+    description: Option<String>,
+}
+
+/// The body of a snippet, a line or a list of lines
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum SnippetBody {
+    Line(String),
+    Lines(Vec<String>),
+}
+
+/// Complete the snippets of the language of the document, if the client supports snippets and
+/// the position is in no string or comment.
+pub(super) fn snippet_completion(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Vec<CompletionItem> {
+    let snippet_support = state
+        .client_capabilities
+        .text_document
+        .as_ref()
+        .and_then(|text_document| text_document.completion.as_ref())
+        .and_then(|completion| completion.completion_item.as_ref())
+        .and_then(|completion_item| completion_item.snippet_support)
+        .unwrap_or(false);
+    let properties = match state.sources.get(uri) {
+        Some(properties) if snippet_support => properties,
+        _ => return vec![],
+    };
+
+    let point = lsp_types::Range::new(position, position);
+    let offset = lsp_range_to_ts_range(&point, &properties.source_code).start_byte;
+    if is_in_string_or_comment(&properties.ast, &properties.source_code, offset) {
+        return vec![];
+    }
+
+    let source = match get_snippets_source(&properties.language_id) {
+        Some(source) => source,
+        None => return vec![],
+    };
+    // ordered by name, for stable results
+    let snippets: BTreeMap<String, Snippet> = match serde_json::from_str(&source) {
+        Ok(snippets) => snippets,
+        Err(err) => {
+            error!("invalid snippets of {}: {}", properties.language_id, err);
+            return vec![];
+        }
+    };
+
+    snippets
+        .into_values()
+        .map(|snippet| CompletionItem {
+            label: snippet.prefix,
+            kind: Some(CompletionItemKind::SNIPPET),
+            detail: snippet.description,
+            insert_text: Some(match snippet.body {
+                SnippetBody::Line(line) => line,
+                SnippetBody::Lines(lines) => lines.join("\n"),
+            }),
+            insert_text_format: Some(InsertTextFormat::SNIPPET),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// Whether the offset is inside a string or a comment, line comments extending to the end of
+/// their line.
+fn is_in_string_or_comment(tree: &tree_sitter::Tree, source_code: &[u8], offset: usize) -> bool {
+    let start = offset.saturating_sub(1);
+    let mut current = tree.root_node().descendant_for_byte_range(start, offset);
+    while let Some(node) = current {
+        let kind = node.kind();
+        if kind.contains("comment") {
+            let is_block = source_code
+                .get(node.start_byte()..node.end_byte())
+                .is_some_and(|text| text.ends_with(b"*/"));
+            if node.start_byte() < offset && (offset < node.end_byte() || !is_block) {
+                return true;
+            }
+        }
+        if kind.contains("string") && node.start_byte() < offset && offset < node.end_byte() {
+            return true;
+        }
+        current = node.parent();
+    }
+    false
+}
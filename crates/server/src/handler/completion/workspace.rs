@@ -0,0 +1,66 @@
+use std::path::Path;
+
+use helper::types::Symbol;
+use lsp_types::{CompletionItem, CompletionItemKind, Url};
+use queries::locals::build_definition_symbols;
+
+use crate::global_state::{is_same_language, GlobalState};
+
+/// Kinds of definitions only completed after a member access
+const MEMBER_KINDS: [CompletionItemKind; 3] = [
+    CompletionItemKind::FIELD,
+    CompletionItemKind::METHOD,
+    CompletionItemKind::PROPERTY,
+];
+
+/// Complete the top-level definitions of the other files of the same language, the opened
+/// documents and the files of the workspace index, each with the file it's defined in.
+pub(super) fn workspace_completion(uri: &Url, state: &GlobalState) -> Vec<CompletionItem> {
+    let language_id = match state.sources.get(uri) {
+        Some(properties) => properties.language_id.as_str(),
+        None => return vec![],
+    };
+
+    let mut items: Vec<CompletionItem> = Vec::new();
+    for (other, tree, source_code, other_language_id) in state.get_files() {
+        if other == uri || !is_same_language(language_id, other_language_id) {
+            continue;
+        }
+        // opened documents outside of the workspace are not indexed
+        let symbols: Vec<Symbol> = match state.workspace_index.files.get(other) {
+            Some(file) => file.symbols.clone(),
+            None => build_definition_symbols(source_code, &tree.root_node(), other_language_id),
+        };
+
+        let file_name = get_file_name(state, other);
+        for symbol in symbols {
+            let kind = *symbol.completion_kind.last().unwrap();
+            if MEMBER_KINDS.contains(&kind) {
+                continue;
+            }
+            items.push(CompletionItem {
+                label: symbol.name,
+                kind: Some(kind),
+                detail: Some(file_name.clone()),
+                ..Default::default()
+            });
+        }
+    }
+    items
+}
+
+/// Get the path of the file relative to the workspace folder it's in, the whole path otherwise.
+fn get_file_name(state: &GlobalState, uri: &Url) -> String {
+    let path = match uri.to_file_path() {
+        Ok(path) => path,
+        Err(_) => return uri.to_string(),
+    };
+    state
+        .workspace_index
+        .roots
+        .iter()
+        .find_map(|root| path.strip_prefix(root).ok())
+        .unwrap_or(Path::new(&path))
+        .display()
+        .to_string()
+}
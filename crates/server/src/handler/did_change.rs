@@ -132,6 +132,7 @@ pub fn did_change(params: DidChangeTextDocumentParams, global_state: &mut Global
 
     // update cache
     global_state.update_source_code(&params.text_document.uri, source_code.clone());
+    global_state.update_version(&params.text_document.uri, params.text_document.version);
 
     // Use final source code and final tree to generate new AST
     let new_tree = match parser.parse(source_code.clone(), Some(&old_tree)) {
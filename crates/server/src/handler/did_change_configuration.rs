@@ -0,0 +1,19 @@
+use log::{debug, error};
+use lsp_types::DidChangeConfigurationParams;
+
+use crate::global_state::GlobalState;
+
+pub fn did_change_configuration(
+    params: DidChangeConfigurationParams,
+    global_state: &mut GlobalState,
+) {
+    debug!("configuration changed: {:?}", params.settings);
+    global_state.config.update(&params.settings);
+
+    // e.g. lints were enabled
+    for uri in global_state.get_opened_uris() {
+        if let Err(e) = global_state.update_diagnostics(&uri) {
+            error!("could not update diagnostics of {}: {}", uri, e);
+        }
+    }
+}
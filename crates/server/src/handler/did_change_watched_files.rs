@@ -0,0 +1,15 @@
+use log::debug;
+use lsp_types::DidChangeWatchedFilesParams;
+
+use crate::global_state::GlobalState;
+
+pub fn did_change_watched_files(
+    params: DidChangeWatchedFilesParams,
+    global_state: &mut GlobalState,
+) {
+    debug!("watched files changed: {:?}", params.changes);
+    // whether created, changed or deleted, the file is read again if it exists
+    for change in params.changes {
+        global_state.update_watched_file(&change.uri);
+    }
+}
@@ -1,5 +1,8 @@
 use log::{debug, error};
-use queries::{highlight::update_identifiers_kind, locals::build_definitions_and_scopes};
+use queries::{
+    children::build_document_symbols, highlight::update_identifiers_kind,
+    locals::build_definitions_and_scopes,
+};
 
 use crate::global_state::GlobalState;
 
@@ -25,10 +28,19 @@ pub fn did_save(params: lsp_types::DidSaveTextDocumentParams, global_state: &mut
         &properties.ast,
         &properties.language_id,
     );
+    let document_symbols = build_document_symbols(
+        &properties.source_code,
+        &properties.ast.root_node(),
+        &properties.language_id,
+    );
+
     // Save it to the global state
     properties.identifiers = identifiers;
     properties.definitions_lookup_map = definitions_lookup_map;
     properties.ordered_scopes = ordered_scopes;
+    properties.document_symbols = document_symbols;
+
+    global_state.update_workspace_index(&params.text_document.uri);
 
     // update diagnostics
     match global_state.update_diagnostics(&params.text_document.uri) {
@@ -0,0 +1,85 @@
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{DocumentHighlight, DocumentHighlightKind, DocumentHighlightParams};
+use queries::locals::{build_write_ranges, find_definition};
+
+use crate::global_state::GlobalState;
+
+/// Highlight the definition and all references of the symbol under the cursor.
+/// Definitions and assignment targets are `Write`, everything else is `Read`.
+pub fn document_highlight(
+    id: RequestId,
+    params: DocumentHighlightParams,
+    state: GlobalState,
+) -> Response {
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let properties = if let Some(properties) = state.sources.get(&uri) {
+        properties
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No properties found for this document".to_string(),
+        );
+    };
+
+    let node = if let Some(node) = get_named_node_by_position(&properties.ast, position) {
+        node
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No node found for this position".to_string(),
+        );
+    };
+
+    let variable_name = if let Ok(variable_name) = node.utf8_text(&properties.source_code) {
+        variable_name
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No variable name found for this node".to_string(),
+        );
+    };
+
+    // no binding under the cursor is not an error, there is just nothing to highlight
+    let definitions = match find_definition(
+        &properties.definitions_lookup_map,
+        variable_name,
+        &node,
+        &properties.ordered_scopes,
+    ) {
+        Some(definitions) => definitions,
+        None => return Response::new_ok(id, serde_json::Value::Null),
+    };
+
+    let write_ranges = build_write_ranges(
+        &properties.source_code,
+        &properties.ast.root_node(),
+        &properties.language_id,
+    );
+
+    let highlights: Vec<DocumentHighlight> = definitions
+        .iter()
+        .map(|symbol| {
+            let is_write = write_ranges.iter().any(|range| {
+                range.start_byte == symbol.location.start_byte
+                    && range.end_byte == symbol.location.end_byte
+            });
+            DocumentHighlight {
+                range: ts_range_to_lsp_range(&symbol.location),
+                kind: Some(if is_write {
+                    DocumentHighlightKind::WRITE
+                } else {
+                    DocumentHighlightKind::READ
+                }),
+            }
+        })
+        .collect();
+
+    let result = serde_json::to_value(&highlights).unwrap();
+    Response::new_ok(id, result)
+}
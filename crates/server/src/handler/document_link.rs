@@ -0,0 +1,177 @@
+use std::path::{Path, PathBuf};
+
+use helper::convert::ts_range_to_lsp_range;
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{DocumentLink, DocumentLinkParams, Url};
+use queries::links::build_links;
+use serde::{Deserialize, Serialize};
+use tree_sitter::Tree;
+
+use crate::{
+    global_state::GlobalState,
+    handler::specifiers::{resolve_specifier, SCRIPT_EXTENSIONS},
+};
+
+/// What's needed to find the target of a link, kept until it's resolved.
+#[derive(Debug, Serialize, Deserialize)]
+struct LinkData {
+    uri: Url,
+    kind: String,
+    target: String,
+}
+
+/// Get the includes and imports of the document, their targets are found on resolve.
+pub fn document_link(id: RequestId, params: DocumentLinkParams, state: GlobalState) -> Response {
+    debug!("got document link request #{}: {:?}", id, params);
+
+    let uri = params.text_document.uri;
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+
+    let document_links: Vec<DocumentLink> =
+        build_links(source_code, &tree.root_node(), language_id)
+            .into_iter()
+            .filter(|link| !link.target.is_empty())
+            .map(|link| DocumentLink {
+                range: ts_range_to_lsp_range(&link.range),
+                target: None,
+                tooltip: None,
+                data: serde_json::to_value(LinkData {
+                    uri: uri.clone(),
+                    kind: link.kind,
+                    target: link.target,
+                })
+                .ok(),
+            })
+            .collect();
+
+    let result = Some(document_links);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Fill in the target of a link, if it can be found.
+pub fn document_link_resolve(
+    id: RequestId,
+    mut params: DocumentLink,
+    state: GlobalState,
+) -> Response {
+    let data = params
+        .data
+        .as_ref()
+        .and_then(|data| serde_json::from_value::<LinkData>(data.clone()).ok());
+
+    if let Some(data) = data {
+        let language_id = state
+            .get_file(&data.uri)
+            .map(|(_, _, language_id)| language_id.to_string())
+            .unwrap_or_default();
+        let directory = data
+            .uri
+            .to_file_path()
+            .ok()
+            .and_then(|path| path.parent().map(Path::to_path_buf));
+
+        if let Some(directory) = directory {
+            params.target = resolve_target(&state, &directory, &language_id, &data)
+                .and_then(|target| Url::from_file_path(target).ok());
+        }
+    }
+
+    let result = serde_json::to_value(&params).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Get the files and packages the file includes or imports which can be found, canonicalized.
+pub(crate) fn get_link_targets(
+    state: &GlobalState,
+    uri: &Url,
+    tree: &Tree,
+    source_code: &Vec<u8>,
+    language_id: &str,
+) -> Vec<PathBuf> {
+    let directory = match uri.to_file_path() {
+        Ok(path) => match path.parent() {
+            Some(directory) => directory.to_path_buf(),
+            None => return vec![],
+        },
+        Err(_) => return vec![],
+    };
+    build_links(source_code, &tree.root_node(), language_id)
+        .into_iter()
+        .filter(|link| !link.target.is_empty())
+        .filter_map(|link| {
+            let data = LinkData {
+                uri: uri.clone(),
+                kind: link.kind,
+                target: link.target,
+            };
+            resolve_target(state, &directory, language_id, &data)
+        })
+        .filter_map(|target| target.canonicalize().ok())
+        .collect()
+}
+
+/// Find the file or directory the link points to, the first candidate which exists wins.
+fn resolve_target(
+    state: &GlobalState,
+    directory: &Path,
+    language_id: &str,
+    data: &LinkData,
+) -> Option<PathBuf> {
+    get_candidates(state, directory, language_id, data)
+        .into_iter()
+        // only Go packages and Hare modules are directories
+        .find(|candidate| {
+            candidate.is_file()
+                || ((data.kind == "package" || language_id == "hare") && candidate.is_dir())
+        })
+}
+
+/// Get the paths the link may point to, in order of preference.
+fn get_candidates(
+    state: &GlobalState,
+    directory: &Path,
+    language_id: &str,
+    data: &LinkData,
+) -> Vec<PathBuf> {
+    let target = data.target.as_str();
+    let paths = resolve_specifier(state, directory, &data.kind, language_id, target);
+    match data.kind.as_str() {
+        "path" if matches!(language_id, "javascript" | "typescript") => paths
+            .into_iter()
+            .flat_map(|base| {
+                let mut candidates = vec![base.clone()];
+                for extension in SCRIPT_EXTENSIONS {
+                    candidates.push(PathBuf::from(format!("{}.{}", base.display(), extension)));
+                }
+                for extension in SCRIPT_EXTENSIONS {
+                    candidates.push(base.join(format!("index.{}", extension)));
+                }
+                candidates
+            })
+            .collect(),
+        "module" if language_id != "hare" => paths
+            .into_iter()
+            .flat_map(|base| {
+                // `from . import a` names the package itself
+                if target.trim_start_matches('.').is_empty() {
+                    return vec![base.join("__init__.py")];
+                }
+                vec![
+                    PathBuf::from(format!("{}.py", base.display())),
+                    base.join("__init__.py"),
+                ]
+            })
+            .collect(),
+        _ => paths,
+    }
+}
@@ -1,6 +1,6 @@
-use helper::convert::ts_range_to_lsp_range;
+use helper::{convert::ts_range_to_lsp_range, types::Symbol};
 use lsp_server::{ErrorCode::ParseError, RequestId, Response};
-use lsp_types::{DocumentSymbol, DocumentSymbolParams};
+use lsp_types::{DocumentSymbol, DocumentSymbolParams, Location, SymbolInformation, Url};
 
 use crate::global_state::GlobalState;
 
@@ -21,41 +21,84 @@ pub fn document_symbol(
         );
     };
 
-    let mut document_symbols: Vec<DocumentSymbol> = Vec::new();
+    let hierarchical_support = state
+        .client_capabilities
+        .text_document
+        .as_ref()
+        .and_then(|text_document| text_document.document_symbol.as_ref())
+        .and_then(|document_symbol| document_symbol.hierarchical_document_symbol_support)
+        .unwrap_or(false);
 
-    let identifiers = &properties.identifiers;
+    let result = if hierarchical_support {
+        let document_symbols: Vec<DocumentSymbol> = properties
+            .document_symbols
+            .iter()
+            .map(to_document_symbol)
+            .collect();
+        serde_json::to_value(&document_symbols).unwrap()
+    } else {
+        // flat list, the nesting is kept in container_name
+        let mut symbol_informations: Vec<SymbolInformation> = Vec::new();
+        flatten_symbols(
+            &uri,
+            &properties.document_symbols,
+            None,
+            &mut symbol_informations,
+        );
+        serde_json::to_value(&symbol_informations).unwrap()
+    };
+
+    lsp_server::Response {
+        id,
+        result: Some(result),
+        error: None,
+    }
+}
 
-    for symbols in identifiers.values() {
-        #[allow(deprecated)]
-        for symbol in symbols {
-            // debug!("symbol: {:?}, {:?}", symbol.name, symbol.belongs_to());
-            document_symbols.push(DocumentSymbol {
-                name: symbol.name.clone(),
-                detail: None,
-                kind: *symbol.symbol_kind.last().unwrap(),
-                tags: None,
+#[allow(deprecated)]
+fn to_document_symbol(symbol: &Symbol) -> DocumentSymbol {
+    DocumentSymbol {
+        name: symbol.name.clone(),
+        detail: symbol.detail.clone(),
+        kind: *symbol.symbol_kind.last().unwrap(),
+        tags: None,
 
-                deprecated: None,
+        deprecated: None,
 
-                // TODO: Return maxium scope
-                // The whole scope of this struct
-                range: ts_range_to_lsp_range(symbol.belongs_to().last().unwrap()),
+        // The whole definition
+        range: ts_range_to_lsp_range(&symbol.definition_range.unwrap_or(symbol.location)),
 
-                // struct name
-                selection_range: ts_range_to_lsp_range(&symbol.location),
+        // The name of the definition
+        selection_range: ts_range_to_lsp_range(&symbol.location),
 
-                children: None,
-            });
-        }
+        children: symbol
+            .children
+            .as_ref()
+            .map(|children| children.iter().map(to_document_symbol).collect()),
     }
+}
 
-    // debug!("Document symbols: {:?}", document_symbols);
-
-    // Only can go to current files
-    let result = serde_json::to_value(&document_symbols).unwrap();
-    lsp_server::Response {
-        id,
-        result: Some(result),
-        error: None,
+#[allow(deprecated)]
+fn flatten_symbols(
+    uri: &Url,
+    symbols: &[Symbol],
+    container_name: Option<&str>,
+    symbol_informations: &mut Vec<SymbolInformation>,
+) {
+    for symbol in symbols {
+        symbol_informations.push(SymbolInformation {
+            name: symbol.name.clone(),
+            kind: *symbol.symbol_kind.last().unwrap(),
+            tags: None,
+            deprecated: None,
+            location: Location {
+                uri: uri.clone(),
+                range: ts_range_to_lsp_range(&symbol.definition_range.unwrap_or(symbol.location)),
+            },
+            container_name: container_name.map(|name| name.to_string()),
+        });
+        if let Some(children) = &symbol.children {
+            flatten_symbols(uri, children, Some(&symbol.name), symbol_informations);
+        }
     }
 }
@@ -0,0 +1,19 @@
+use log::debug;
+use lsp_server::{ErrorCode::InvalidParams, RequestId, Response};
+use lsp_types::ExecuteCommandParams;
+
+use crate::{caps::NO_OP_COMMAND, global_state::GlobalState};
+
+/// Run a command of the server, the only one does nothing.
+pub fn execute_command(id: RequestId, params: ExecuteCommandParams, _: GlobalState) -> Response {
+    debug!("got execute command request #{}: {:?}", id, params);
+
+    if params.command == NO_OP_COMMAND {
+        return Response::new_ok(id, serde_json::Value::Null);
+    }
+    Response::new_err(
+        id,
+        InvalidParams as i32,
+        format!("Unknown command {}", params.command),
+    )
+}
@@ -1,5 +1,8 @@
 use lsp_server::{ErrorCode::ParseError, RequestId, Response};
-use lsp_types::{error_codes::REQUEST_CANCELLED, DocumentFormattingParams, TextEdit};
+use lsp_types::{
+    error_codes::REQUEST_CANCELLED, DocumentFormattingParams, DocumentRangeFormattingParams,
+    FormattingOptions, Url,
+};
 use queries::indents::text_edits;
 
 use crate::global_state::GlobalState;
@@ -9,7 +12,45 @@ pub fn format(
     params: DocumentFormattingParams,
     global_state: GlobalState,
 ) -> Response {
-    let source_code = match global_state.get_source_code(&params.text_document.uri) {
+    indent(
+        id,
+        &params.text_document.uri,
+        0..usize::MAX,
+        &params.options,
+        &global_state,
+    )
+}
+
+/// Re-indent the lines touched by the range only.
+pub fn range_format(
+    id: RequestId,
+    params: DocumentRangeFormattingParams,
+    global_state: GlobalState,
+) -> Response {
+    let start = params.range.start.line as usize;
+    let mut end = params.range.end.line as usize;
+    // a selection of whole lines ends at the start of the next one
+    if end > start && params.range.end.character == 0 {
+        end -= 1;
+    }
+
+    indent(
+        id,
+        &params.text_document.uri,
+        start..end + 1,
+        &params.options,
+        &global_state,
+    )
+}
+
+fn indent(
+    id: RequestId,
+    uri: &Url,
+    rows: std::ops::Range<usize>,
+    options: &FormattingOptions,
+    global_state: &GlobalState,
+) -> Response {
+    let source_code = match global_state.get_source_code(uri) {
         Some(source_code) => source_code,
         None => {
             return Response::new_err(
@@ -20,7 +61,7 @@ pub fn format(
         }
     };
 
-    let language = match global_state.get_language_id(&params.text_document.uri) {
+    let language = match global_state.get_language_id(uri) {
         Some(language) => language,
         None => {
             return Response::new_err(
@@ -31,7 +72,7 @@ pub fn format(
         }
     };
 
-    let old_tree = match global_state.get_tree(&params.text_document.uri) {
+    let old_tree = match global_state.get_tree(uri) {
         Some(tree) => tree,
         None => {
             return Response::new_err(id, REQUEST_CANCELLED as i32, "Tree not found".to_string())
@@ -39,7 +80,14 @@ pub fn format(
     };
 
     // get text edit
-    let text_edits = match text_edits(source_code, &language, old_tree) {
+    let text_edits = match text_edits(
+        &source_code,
+        &language,
+        old_tree,
+        rows,
+        options.tab_size as usize,
+        options.insert_spaces,
+    ) {
         Ok(text_edits) => text_edits,
         Err(e) => return Response::new_err(id, ParseError as i32, e),
     };
@@ -0,0 +1,103 @@
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    request::{GotoDeclarationParams, GotoDeclarationResponse},
+    Location, Position, Url,
+};
+use queries::{declarations::build_declarations, locals::find_definition};
+
+use super::goto_definition::find_definitions;
+use crate::global_state::GlobalState;
+
+/// Go to the prototypes, forward and extern declarations of the name at the position.
+/// Names which are never declared apart go to their definitions.
+pub fn goto_declaration(
+    id: RequestId,
+    params: GotoDeclarationParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got gotoDeclaration request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let locations = match find_declarations(&uri, position, &state) {
+        Ok(locations) if !locations.is_empty() => locations,
+        Ok(_) => match find_definitions(&uri, position, &state) {
+            Ok(locations) => locations,
+            Err(message) => return Response::new_err(id, ParseError as i32, message.to_string()),
+        },
+        Err(message) => return Response::new_err(id, ParseError as i32, message.to_string()),
+    };
+
+    let result = Some(GotoDeclarationResponse::Array(locations));
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+fn find_declarations(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Result<Vec<Location>, &'static str> {
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(uri) {
+        file
+    } else {
+        return Err("No tree found for this document");
+    };
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Err("No node found for this position");
+    };
+    let name = node.utf8_text(source_code).unwrap_or_default();
+
+    // declarations of the local binding
+    let declarations = build_declarations(source_code, &tree.root_node(), language_id);
+    if let Some(properties) = state.sources.get(uri) {
+        if let Some(occurrences) = find_definition(
+            &properties.definitions_lookup_map,
+            name,
+            &node,
+            &properties.ordered_scopes,
+        ) {
+            let locations: Vec<Location> = occurrences
+                .iter()
+                .filter(|occurrence| {
+                    declarations
+                        .iter()
+                        .any(|range| range.start_byte == occurrence.location.start_byte)
+                })
+                .map(|occurrence| Location {
+                    uri: uri.clone(),
+                    range: ts_range_to_lsp_range(&occurrence.location),
+                })
+                .collect();
+            if !locations.is_empty() {
+                return Ok(locations);
+            }
+        }
+    }
+
+    // e.g. a prototype in a header, or a forward declaration which is not a binding
+    let mut locations: Vec<Location> = Vec::new();
+    for (file_uri, tree, source_code, language_id) in state.get_files() {
+        locations.extend(
+            build_declarations(source_code, &tree.root_node(), language_id)
+                .iter()
+                .filter(|range| {
+                    source_code
+                        .get(range.start_byte..range.end_byte)
+                        .map(|text| text == name.as_bytes())
+                        .unwrap_or(false)
+                })
+                .map(|range| Location {
+                    uri: file_uri.clone(),
+                    range: ts_range_to_lsp_range(range),
+                }),
+        );
+    }
+
+    Ok(locations)
+}
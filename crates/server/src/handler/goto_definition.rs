@@ -1,91 +1,133 @@
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
 use log::{debug, error};
-use lsp_server::ErrorCode::ParseError;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    GotoDefinitionParams, GotoDefinitionResponse, Location, Position, SymbolKind, Url,
+};
+use queries::{
+    declarations::build_declarations,
+    locals::{build_definition_ranges, find_definition},
+};
 
-use crate::global_state::GlobalState;
+use crate::global_state::{is_same_language, GlobalState};
+
+/// Kinds of definitions only reachable through a member access, never by their bare name
+const MEMBER_KINDS: [SymbolKind; 3] = [SymbolKind::FIELD, SymbolKind::METHOD, SymbolKind::PROPERTY];
 
 pub fn goto_definition(
-    id: lsp_server::RequestId,
-    params: lsp_types::GotoDefinitionParams,
-    global_state: GlobalState,
-) -> lsp_server::Response {
+    id: RequestId,
+    params: GotoDefinitionParams,
+    state: GlobalState,
+) -> Response {
     debug!("got gotoDefinition request #{}: {:?}", id, params);
-    let properties = global_state
-        .sources
-        .get(&params.text_document_position_params.text_document.uri)
-        .unwrap();
-    let tree = &properties.ast;
-    let scopes = &properties.ordered_scopes;
-    let source_code = &properties.source_code;
-
-    let node_position = params.text_document_position_params.position;
-    let point = tree_sitter::Point::new(
-        node_position.line.try_into().unwrap(),
-        node_position.character.try_into().unwrap(),
-    );
 
-    // Find that node
-    let root_node = tree.root_node();
-    let tree_cursor = root_node.walk();
-    let ranges: Vec<lsp_types::Range> = match tree_cursor
-        .node()
-        .named_descendant_for_point_range(point, point)
-    {
-        Some(node) => {
-            // check current scope
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
 
-            let loopup_table = &properties.definitions_lookup_map;
-            let smallest_scope_id = queries::utils::get_smallest_scope_id_by_node(&node, scopes);
+    let locations = match find_definitions(&uri, position, &state) {
+        Ok(locations) => locations,
+        Err(message) => {
+            error!("could not find definition: {}", message);
+            return Response::new_err(id, ParseError as i32, message.to_string());
+        }
+    };
 
-            let variable_name = node.utf8_text(source_code).unwrap().to_owned();
+    let result = Some(GotoDefinitionResponse::Array(locations));
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
 
-            let key = format!("{}:{}", variable_name, smallest_scope_id);
-            let definitions = match loopup_table.get(&key) {
-                Some(definitions) => definitions,
-                None => {
-                    error!("could not find definition for {}", key);
-                    return lsp_server::Response::new_err(
-                        id,
-                        ParseError as i32,
-                        "could not find definition for this variable".to_string(),
-                    );
-                }
-            };
+/// Find the definitions of the name at the position. Definitions with a body are preferred over
+/// prototypes and other declarations, those of the top-level definitions of the other files of
+/// the same language included.
+pub(crate) fn find_definitions(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Result<Vec<Location>, &'static str> {
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(uri) {
+        file
+    } else {
+        return Err("No tree found for this document");
+    };
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Err("No node found for this position");
+    };
+    let name = node.utf8_text(source_code).unwrap_or_default();
 
-            let range = &definitions[0];
-            let start = lsp_types::Position {
-                line: range.location.start_point.row.try_into().unwrap(),
-                character: range.location.start_point.column.try_into().unwrap(),
-            };
-            let end = lsp_types::Position {
-                line: range.location.end_point.row.try_into().unwrap(),
-                character: range.location.end_point.column.try_into().unwrap(),
-            };
-            let range = lsp_types::Range { start, end };
+    // the binding may only be declared here, e.g. a prototype of a function of another file
+    let mut declaration: Option<Location> = None;
+    if let Some(properties) = state.sources.get(uri) {
+        if let Some(occurrences) = find_definition(
+            &properties.definitions_lookup_map,
+            name,
+            &node,
+            &properties.ordered_scopes,
+        ) {
+            let definitions = build_definition_ranges(source_code, &tree.root_node(), language_id);
+            let declarations = build_declarations(source_code, &tree.root_node(), language_id);
+            let bodies: Vec<Location> = occurrences
+                .iter()
+                .filter(|occurrence| {
+                    let start_byte = occurrence.location.start_byte;
+                    definitions
+                        .iter()
+                        .any(|range| range.start_byte == start_byte)
+                        && !declarations
+                            .iter()
+                            .any(|range| range.start_byte == start_byte)
+                })
+                .map(|occurrence| Location {
+                    uri: uri.clone(),
+                    range: ts_range_to_lsp_range(&occurrence.location),
+                })
+                .collect();
+            if !bodies.is_empty() {
+                return Ok(bodies);
+            }
 
-            vec![range]
-        }
-        None => {
-            error!("no node found");
-            vec![]
+            declaration = occurrences.first().map(|occurrence| Location {
+                uri: uri.clone(),
+                range: ts_range_to_lsp_range(&occurrence.location),
+            });
         }
-    };
+    }
 
-    // Only can go to current files
-    let location = vec![lsp_types::Location {
-        uri: params.text_document_position_params.text_document.uri,
-        range: lsp_types::Range {
-            start: lsp_types::Position {
-                line: ranges[0].start.line,
-                character: ranges[0].start.character,
-            },
-            end: lsp_types::Position {
-                line: ranges[0].end.line,
-                character: ranges[0].end.character,
-            },
-        },
-    }];
+    let mut locations: Vec<Location> = Vec::new();
+    for (file_uri, file) in state.workspace_index.files.iter() {
+        if file_uri == uri
+            || !is_same_language(language_id, &file.language_id)
+            || !file.symbols.iter().any(|symbol| symbol.name == name)
+        {
+            continue;
+        }
+        let declarations =
+            build_declarations(&file.source_code, &file.ast.root_node(), &file.language_id);
+        locations.extend(
+            file.symbols
+                .iter()
+                .filter(|symbol| {
+                    symbol.name == name
+                        && !symbol
+                            .symbol_kind
+                            .iter()
+                            .any(|kind| MEMBER_KINDS.contains(kind))
+                        && !declarations
+                            .iter()
+                            .any(|range| range.start_byte == symbol.location.start_byte)
+                })
+                .map(|symbol| Location {
+                    uri: file_uri.clone(),
+                    range: ts_range_to_lsp_range(&symbol.location),
+                }),
+        );
+    }
 
-    let result = Some(lsp_types::GotoDefinitionResponse::Array(location));
-    let result = serde_json::to_value(&result).unwrap();
-    lsp_server::Response::new_ok(id, result)
+    match (locations.is_empty(), declaration) {
+        (false, _) => Ok(locations),
+        (true, Some(declaration)) => Ok(vec![declaration]),
+        (true, None) => Err("could not find definition for this variable"),
+    }
 }
@@ -0,0 +1,228 @@
+use std::collections::HashSet;
+
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    request::{GotoImplementationParams, GotoImplementationResponse},
+    Location, Position, SymbolKind, Url,
+};
+use queries::{
+    hierarchy::build_type_relations,
+    implementations::{build_methods, Method},
+    utils::get_query_source,
+};
+
+use crate::global_state::{is_same_language, GlobalState};
+
+/// Go to the types implementing the interface at the position, or to the methods implementing
+/// the abstract method at the position.
+pub fn goto_implementation(
+    id: RequestId,
+    params: GotoImplementationParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got gotoImplementation request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let locations = match find_implementations(&uri, position, &state) {
+        Ok(locations) => locations,
+        Err(message) => return Response::new_err(id, ParseError as i32, message.to_string()),
+    };
+
+    let result = Some(GotoImplementationResponse::Array(locations));
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+fn find_implementations(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Result<Vec<Location>, &'static str> {
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(uri) {
+        file
+    } else {
+        return Err("No tree found for this document");
+    };
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Err("No node found for this position");
+    };
+
+    // on a method, its implementations are the methods with the same name of the subtypes
+    let (type_name, method_name) = match build_methods(source_code, &tree.root_node(), language_id)
+        .into_iter()
+        .find(|method| method.range.start_byte == node.start_byte())
+    {
+        Some(method) => (method.owner, Some(method.name)),
+        None => (
+            node.utf8_text(source_code).unwrap_or_default().to_string(),
+            None,
+        ),
+    };
+
+    let files: Vec<_> = state
+        .get_files()
+        .into_iter()
+        .filter(|(_, _, _, file_language_id)| is_same_language(language_id, file_language_id))
+        .collect();
+    let methods: Vec<(&Url, Method)> = files
+        .iter()
+        .flat_map(|(file_uri, tree, source_code, language_id)| {
+            build_methods(source_code, &tree.root_node(), language_id)
+                .into_iter()
+                .map(move |method| (*file_uri, method))
+        })
+        .collect();
+
+    // languages without inheritance, e.g. Go, implement interfaces by having their methods
+    let is_structural = get_query_source(language_id, "hierarchy").is_none();
+    let mut locations: Vec<Location> = Vec::new();
+    let subtypes: HashSet<String> = if is_structural {
+        let subtypes = get_structural_subtypes(&type_name, &methods);
+        if method_name.is_none() {
+            for subtype in subtypes.iter() {
+                locations.extend(get_type_locations(state, language_id, subtype, &methods));
+            }
+        }
+        subtypes
+    } else {
+        // every subtype and the relation it's declared by, e.g. `impl Trait for Type`
+        let mut subtypes: HashSet<String> = HashSet::new();
+        let mut supertypes: Vec<String> = vec![type_name.clone()];
+        while let Some(supertype) = supertypes.pop() {
+            for (file_uri, tree, source_code, language_id) in files.iter() {
+                for relation in build_type_relations(source_code, &tree.root_node(), language_id) {
+                    if relation.supertype != supertype {
+                        continue;
+                    }
+                    if method_name.is_none() {
+                        locations.push(Location {
+                            uri: (*file_uri).clone(),
+                            range: ts_range_to_lsp_range(&relation.subtype_range),
+                        });
+                    }
+                    if subtypes.insert(relation.subtype.clone()) {
+                        supertypes.push(relation.subtype);
+                    }
+                }
+            }
+        }
+        subtypes
+    };
+
+    if let Some(method_name) = method_name {
+        locations.extend(
+            methods
+                .iter()
+                .filter(|(file_uri, method)| {
+                    method.name == method_name
+                        && subtypes.contains(&method.owner)
+                        && is_implementation_of(state, file_uri, method, &type_name)
+                })
+                .map(|(file_uri, method)| Location {
+                    uri: (*file_uri).clone(),
+                    range: ts_range_to_lsp_range(&method.range),
+                }),
+        );
+    }
+
+    Ok(locations)
+}
+
+/// Whether the method may implement one of the interface, e.g. only those of an
+/// `impl Interface for Type` in rust and not those of `impl Type` or of another trait.
+fn is_implementation_of(state: &GlobalState, uri: &Url, method: &Method, interface: &str) -> bool {
+    let (tree, source_code, _) = match state.get_file(uri) {
+        Some(file) => file,
+        None => return true,
+    };
+    let mut node = tree
+        .root_node()
+        .named_descendant_for_byte_range(method.range.start_byte, method.range.end_byte);
+    while let Some(current) = node {
+        if current.kind() == "impl_item" {
+            // `a::Trait<T>` names `Trait`
+            let implemented = current
+                .child_by_field_name("trait")
+                .and_then(|implemented| implemented.utf8_text(source_code).ok())
+                .map(|implemented| implemented.split('<').next().unwrap_or_default())
+                .and_then(|implemented| implemented.rsplit("::").next());
+            return implemented == Some(interface);
+        }
+        node = current.parent();
+    }
+    true
+}
+
+/// Get the types which have every method of the interface.
+fn get_structural_subtypes(interface: &str, methods: &[(&Url, Method)]) -> HashSet<String> {
+    let required: HashSet<&str> = methods
+        .iter()
+        .filter(|(_, method)| method.owner == interface)
+        .map(|(_, method)| method.name.as_str())
+        .collect();
+    if required.is_empty() {
+        return HashSet::new();
+    }
+
+    let owners: HashSet<&str> = methods
+        .iter()
+        .map(|(_, method)| method.owner.as_str())
+        .filter(|owner| *owner != interface)
+        .collect();
+    owners
+        .into_iter()
+        .filter(|owner| {
+            required.iter().all(|name| {
+                methods
+                    .iter()
+                    .any(|(_, method)| method.owner == *owner && method.name == *name)
+            })
+        })
+        .map(|owner| owner.to_string())
+        .collect()
+}
+
+/// Get the definitions of the type from the index of the language, or its methods when it's not
+/// indexed.
+fn get_type_locations(
+    state: &GlobalState,
+    language_id: &str,
+    name: &str,
+    methods: &[(&Url, Method)],
+) -> Vec<Location> {
+    let locations: Vec<Location> = state
+        .workspace_index
+        .files
+        .iter()
+        .filter(|(_, file)| is_same_language(language_id, &file.language_id))
+        .flat_map(|(file_uri, file)| {
+            file.symbols
+                .iter()
+                .filter(|symbol| {
+                    symbol.name == name && symbol.symbol_kind.contains(&SymbolKind::CLASS)
+                })
+                .map(move |symbol| Location {
+                    uri: file_uri.clone(),
+                    range: ts_range_to_lsp_range(&symbol.location),
+                })
+        })
+        .collect();
+    if !locations.is_empty() {
+        return locations;
+    }
+
+    methods
+        .iter()
+        .filter(|(_, method)| method.owner == name)
+        .map(|(file_uri, method)| Location {
+            uri: (*file_uri).clone(),
+            range: ts_range_to_lsp_range(&method.owner_range),
+        })
+        .collect()
+}
@@ -0,0 +1,175 @@
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    request::{GotoTypeDefinitionParams, GotoTypeDefinitionResponse},
+    Location, Position, SymbolKind, Url,
+};
+use queries::{declarations::build_declarations, locals::find_definition};
+use tree_sitter::Node;
+
+use crate::global_state::{is_same_language, GlobalState};
+
+/// Types of grammars which have no `type` field, e.g. Kotlin
+const TYPE_NODES: [&str; 2] = ["user_type", "nullable_type"];
+
+/// Parts of the kinds of the nodes the type of a name is not looked for above: the declaration
+/// itself, e.g. an untyped `let x = 5;`, or the statement or scope enclosing it
+const BOUNDARY_KINDS: [&str; 7] = [
+    "declaration",
+    "statement",
+    "definition",
+    "parameter",
+    "item",
+    "block",
+    "body",
+];
+
+/// How far the declaration may be from the name, e.g. `int **a = 0` nests it in two pointer
+/// declarators and an init declarator
+const MAX_DECLARATOR_DEPTH: usize = 5;
+
+/// Go to the definition of the type the variable or parameter at the position is declared with.
+pub fn goto_type_definition(
+    id: RequestId,
+    params: GotoTypeDefinitionParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got gotoTypeDefinition request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let locations = match find_type_definitions(&uri, position, &state) {
+        Ok(locations) => locations,
+        Err(message) => return Response::new_err(id, ParseError as i32, message.to_string()),
+    };
+
+    let result = Some(GotoTypeDefinitionResponse::Array(locations));
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+fn find_type_definitions(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Result<Vec<Location>, &'static str> {
+    let properties = if let Some(properties) = state.sources.get(uri) {
+        properties
+    } else {
+        return Err("No properties found for this document");
+    };
+    let tree = &properties.ast;
+    let source_code = &properties.source_code;
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Err("No node found for this position");
+    };
+
+    // on a type already, otherwise on a binding declared with one
+    let type_node = if node.kind().contains("type") {
+        node
+    } else {
+        let name = node.utf8_text(source_code).unwrap_or_default();
+        let definition = find_definition(
+            &properties.definitions_lookup_map,
+            name,
+            &node,
+            &properties.ordered_scopes,
+        )
+        .and_then(|occurrences| occurrences.first())
+        .and_then(|definition| {
+            tree.root_node().named_descendant_for_byte_range(
+                definition.location.start_byte,
+                definition.location.end_byte,
+            )
+        });
+        match definition
+            .and_then(|definition| get_declared_type(&definition))
+            .and_then(|declared_type| get_type_identifier(&declared_type))
+        {
+            Some(type_node) => type_node,
+            None => return Err("No type found for this variable"),
+        }
+    };
+    let type_name = type_node.utf8_text(source_code).unwrap_or_default();
+
+    // a type of the document shadows those of other files
+    let local_definition = find_definition(
+        &properties.definitions_lookup_map,
+        type_name,
+        &type_node,
+        &properties.ordered_scopes,
+    )
+    .and_then(|occurrences| occurrences.first());
+    if let Some(definition) = local_definition {
+        return Ok(vec![Location {
+            uri: uri.clone(),
+            range: ts_range_to_lsp_range(&definition.location),
+        }]);
+    }
+
+    let mut locations: Vec<Location> = Vec::new();
+    for (file_uri, file) in state.workspace_index.files.iter() {
+        if file_uri == uri || !is_same_language(&properties.language_id, &file.language_id) {
+            continue;
+        }
+        let declarations =
+            build_declarations(&file.source_code, &file.ast.root_node(), &file.language_id);
+        locations.extend(
+            file.symbols
+                .iter()
+                .filter(|symbol| {
+                    symbol.name == type_name
+                        && symbol.symbol_kind.contains(&SymbolKind::CLASS)
+                        && !declarations
+                            .iter()
+                            .any(|range| range.start_byte == symbol.location.start_byte)
+                })
+                .map(|symbol| Location {
+                    uri: file_uri.clone(),
+                    range: ts_range_to_lsp_range(&symbol.location),
+                }),
+        );
+    }
+    Ok(locations)
+}
+
+/// Get the type node of the declaration the name belongs to, None if it's declared without one.
+pub(crate) fn get_declared_type<'a>(name_node: &Node<'a>) -> Option<Node<'a>> {
+    let mut node = name_node.parent();
+    for _ in 0..MAX_DECLARATOR_DEPTH {
+        let current = node?;
+        if let Some(declared_type) = current.child_by_field_name("type") {
+            return Some(declared_type);
+        }
+        let mut cursor = current.walk();
+        let declared_type = current
+            .named_children(&mut cursor)
+            .find(|child| TYPE_NODES.contains(&child.kind()));
+        if declared_type.is_some() {
+            return declared_type;
+        }
+        if BOUNDARY_KINDS
+            .iter()
+            .any(|boundary| current.kind().contains(boundary))
+        {
+            return None;
+        }
+        node = current.parent();
+    }
+    None
+}
+
+/// Get the first type name of a type, e.g. `Foo` in `*const Foo` or `struct Foo`.
+/// Primitive types have none.
+pub(crate) fn get_type_identifier<'a>(declared_type: &Node<'a>) -> Option<Node<'a>> {
+    if declared_type.kind() == "type_identifier" {
+        return Some(*declared_type);
+    }
+    let mut cursor = declared_type.walk();
+    let children: Vec<Node> = declared_type.named_children(&mut cursor).collect();
+    children.iter().find_map(|child| get_type_identifier(child))
+}
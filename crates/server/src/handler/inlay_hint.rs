@@ -0,0 +1,320 @@
+use helper::{
+    convert::{lsp_range_to_ts_range, ts_point_to_lsp_position, ts_range_to_lsp_range},
+    tree_walker::get_named_node_by_position,
+};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    InlayHint, InlayHintKind, InlayHintLabel, InlayHintParams, InlayHintTooltip, Location,
+    SymbolKind, Url,
+};
+use queries::{
+    implementations::build_methods,
+    locals::{find_definition, find_parameter_list, get_parameter_names},
+};
+use serde_json::json;
+use tree_sitter::Node;
+
+use crate::{
+    global_state::{is_same_language, GlobalState, Properties},
+    handler::completion::members::infer_receiver_type,
+};
+
+/// The implicit receiver of methods, it's not passed as an argument
+const RECEIVERS: [&str; 2] = ["self", "cls"];
+
+/// Fields of the member accesses holding the name of the method, e.g. `attribute` of `a.f` in
+/// python
+const MEMBER_FIELDS: [&str; 3] = ["field", "property", "attribute"];
+
+/// Fields of the member accesses holding the receiver, e.g. `object` of `a.f` in python
+const OBJECT_FIELDS: [&str; 4] = ["object", "value", "argument", "operand"];
+
+/// Arguments after which the positions no longer match the parameters
+const NON_POSITIONAL_ARGUMENTS: [&str; 4] = [
+    "keyword_argument",
+    "list_splat",
+    "dictionary_splat",
+    "spread_element",
+];
+
+/// Label the arguments of calls with the names of the parameters.
+pub fn inlay_hint(id: RequestId, params: InlayHintParams, state: GlobalState) -> Response {
+    debug!("got inlay hint request #{}: {:?}", id, params);
+
+    let uri = params.text_document.uri;
+    let properties = if let Some(properties) = state.sources.get(&uri) {
+        properties
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No properties found for this document".to_string(),
+        );
+    };
+
+    let mut inlay_hints: Vec<InlayHint> = Vec::new();
+    if state.config.inlay_hints.parameter_names {
+        let range = lsp_range_to_ts_range(&params.range, &properties.source_code);
+        let mut calls: Vec<Node> = Vec::new();
+        collect_calls(
+            properties.ast.root_node(),
+            range.start_byte,
+            range.end_byte,
+            &mut calls,
+        );
+
+        for call in calls {
+            get_parameter_hints(&uri, &call, properties, &state, &mut inlay_hints);
+        }
+    }
+
+    let result = Some(inlay_hints);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Show the parameter list of the callee on hover.
+pub fn inlay_hint_resolve(id: RequestId, mut params: InlayHint, state: GlobalState) -> Response {
+    let definition = params
+        .data
+        .as_ref()
+        .and_then(|data| serde_json::from_value::<Location>(data.clone()).ok());
+
+    if let Some(Location { uri, range }) = definition {
+        if let Some((tree, source_code, _)) = state.get_file(&uri) {
+            let parameter_list =
+                get_named_node_by_position(tree, range.start).and_then(|name_node| {
+                    find_parameter_list(&name_node).map(|list| (name_node, list))
+                });
+            if let Some((name_node, parameter_list)) = parameter_list {
+                let signature = format!(
+                    "{}{}",
+                    name_node.utf8_text(source_code).unwrap_or_default(),
+                    parameter_list.utf8_text(source_code).unwrap_or_default()
+                );
+                params.tooltip = Some(InlayHintTooltip::String(
+                    signature.split_whitespace().collect::<Vec<_>>().join(" "),
+                ));
+            }
+        }
+    }
+
+    let result = serde_json::to_value(&params).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Collect calls overlapping the byte range, calls in arguments included.
+fn collect_calls<'a>(
+    node: Node<'a>,
+    start_byte: usize,
+    end_byte: usize,
+    calls: &mut Vec<Node<'a>>,
+) {
+    if node.end_byte() < start_byte || node.start_byte() > end_byte {
+        return;
+    }
+    if node.child_by_field_name("function").is_some()
+        && node.child_by_field_name("arguments").is_some()
+    {
+        calls.push(node);
+    }
+    for i in 0..node.named_child_count() {
+        if let Some(child) = node.named_child(i) {
+            collect_calls(child, start_byte, end_byte, calls);
+        }
+    }
+}
+
+fn get_parameter_hints(
+    uri: &Url,
+    call: &Node,
+    properties: &Properties,
+    state: &GlobalState,
+    inlay_hints: &mut Vec<InlayHint>,
+) {
+    let source_code = &properties.source_code;
+    let callee = match call.child_by_field_name("function") {
+        Some(callee) => callee,
+        None => return,
+    };
+
+    // `f(x)` or `object.f(x)`
+    let (name_node, receiver) = if callee.kind().ends_with("identifier") {
+        (callee, None)
+    } else {
+        let name_node = MEMBER_FIELDS
+            .iter()
+            .find_map(|field| callee.child_by_field_name(field));
+        let receiver = OBJECT_FIELDS
+            .iter()
+            .find_map(|field| callee.child_by_field_name(field));
+        match (name_node, receiver) {
+            (Some(name_node), Some(receiver)) => (name_node, Some(receiver)),
+            _ => return,
+        }
+    };
+    let name = name_node.utf8_text(source_code).unwrap_or_default();
+
+    let (definition, mut parameters) =
+        match resolve_parameters(uri, &name_node, name, receiver, properties, state) {
+            Some(resolved) => resolved,
+            None => return,
+        };
+    if receiver.is_some()
+        && parameters
+            .first()
+            .map(|parameter| RECEIVERS.contains(&parameter.as_str()))
+            .unwrap_or(false)
+    {
+        parameters.remove(0);
+    }
+
+    let arguments = match call.child_by_field_name("arguments") {
+        Some(arguments) => arguments,
+        None => return,
+    };
+    let mut cursor = arguments.walk();
+    let arguments: Vec<Node> = arguments
+        .named_children(&mut cursor)
+        .filter(|argument| !argument.kind().contains("comment"))
+        .collect();
+
+    for (argument, parameter) in arguments.iter().zip(parameters.iter()) {
+        if NON_POSITIONAL_ARGUMENTS.contains(&argument.kind()) {
+            break;
+        }
+        // `f(a)` for `f(a)` says nothing new
+        if argument.kind().ends_with("identifier")
+            && argument.utf8_text(source_code).unwrap_or_default() == parameter
+        {
+            continue;
+        }
+        inlay_hints.push(InlayHint {
+            position: ts_point_to_lsp_position(&argument.start_position()),
+            label: InlayHintLabel::String(format!("{}:", parameter)),
+            kind: Some(InlayHintKind::PARAMETER),
+            text_edits: None,
+            tooltip: None,
+            padding_left: None,
+            padding_right: Some(true),
+            data: Some(json!(definition)),
+        });
+    }
+}
+
+/// Find the definition of the callee, in the document first then in the other files of the same
+/// language. Methods are looked up among those of the type of the receiver, and not at all if it
+/// can't be inferred.
+fn resolve_parameters(
+    uri: &Url,
+    name_node: &Node,
+    name: &str,
+    receiver: Option<Node>,
+    properties: &Properties,
+    state: &GlobalState,
+) -> Option<(Location, Vec<String>)> {
+    if let Some(receiver) = receiver {
+        let type_name =
+            infer_receiver_type(uri, &receiver, state).filter(|name| !name.is_empty())?;
+        return resolve_method_parameters(uri, name, &type_name, properties, state);
+    }
+
+    if let Some(definition) = find_definition(
+        &properties.definitions_lookup_map,
+        name,
+        name_node,
+        &properties.ordered_scopes,
+    )
+    .and_then(|occurrences| occurrences.first())
+    {
+        let definition_node = properties.ast.root_node().named_descendant_for_byte_range(
+            definition.location.start_byte,
+            definition.location.end_byte,
+        )?;
+        let parameters = get_parameter_names(
+            &properties.source_code,
+            &definition_node,
+            &properties.language_id,
+        )?;
+        return Some((
+            Location {
+                uri: uri.clone(),
+                range: ts_range_to_lsp_range(&definition.location),
+            },
+            parameters,
+        ));
+    }
+
+    // the document itself is indexed too, prefer it over other files
+    let mut files: Vec<(&Url, _)> = state
+        .workspace_index
+        .files
+        .iter()
+        .filter(|(_, file)| is_same_language(&properties.language_id, &file.language_id))
+        .collect();
+    files.sort_by_key(|(file_uri, _)| *file_uri != uri);
+
+    files.into_iter().find_map(|(file_uri, file)| {
+        file.symbols
+            .iter()
+            .filter(|symbol| {
+                symbol.name == name
+                    && symbol
+                        .symbol_kind
+                        .iter()
+                        .any(|kind| *kind == SymbolKind::FUNCTION || *kind == SymbolKind::METHOD)
+            })
+            .find_map(|symbol| {
+                let definition_node = file.ast.root_node().named_descendant_for_byte_range(
+                    symbol.location.start_byte,
+                    symbol.location.end_byte,
+                )?;
+                let parameters =
+                    get_parameter_names(&file.source_code, &definition_node, &file.language_id)?;
+                Some((
+                    Location {
+                        uri: file_uri.clone(),
+                        range: ts_range_to_lsp_range(&symbol.location),
+                    },
+                    parameters,
+                ))
+            })
+    })
+}
+
+/// Find the method of the type with the name, in the document first then in the other files of
+/// the same language.
+fn resolve_method_parameters(
+    uri: &Url,
+    name: &str,
+    type_name: &str,
+    properties: &Properties,
+    state: &GlobalState,
+) -> Option<(Location, Vec<String>)> {
+    let mut files = state.files_mentioning(type_name, &properties.language_id);
+    files.sort_by_key(|(file_uri, _, _, _)| *file_uri != uri);
+
+    files
+        .into_iter()
+        .find_map(|(file_uri, tree, source_code, language_id)| {
+            build_methods(source_code, &tree.root_node(), language_id)
+                .into_iter()
+                .filter(|method| method.owner == type_name && method.name == name)
+                .find_map(|method| {
+                    let definition_node = tree.root_node().named_descendant_for_byte_range(
+                        method.range.start_byte,
+                        method.range.end_byte,
+                    )?;
+                    let parameters =
+                        get_parameter_names(source_code, &definition_node, language_id)?;
+                    Some((
+                        Location {
+                            uri: file_uri.clone(),
+                            range: ts_range_to_lsp_range(&method.range),
+                        },
+                        parameters,
+                    ))
+                })
+        })
+}
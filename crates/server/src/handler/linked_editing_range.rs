@@ -0,0 +1,47 @@
+use helper::convert::{lsp_position_to_ts_point, ts_range_to_lsp_range};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{LinkedEditingRangeParams, LinkedEditingRanges};
+use queries::linked::build_linked_ranges;
+
+use crate::global_state::GlobalState;
+
+/// Get the ranges edited together with the one at the position, e.g. the opening and closing
+/// tag names of a JSX element.
+pub fn linked_editing_range(
+    id: RequestId,
+    params: LinkedEditingRangeParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got linked editing range request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+
+    // the cursor may be right after the name, while typing
+    let point = lsp_position_to_ts_point(&position);
+    let result = build_linked_ranges(source_code, &tree.root_node(), language_id)
+        .into_iter()
+        .find(|ranges| {
+            ranges
+                .iter()
+                .any(|range| range.start_point <= point && point <= range.end_point)
+        })
+        .map(|ranges| LinkedEditingRanges {
+            ranges: ranges.iter().map(ts_range_to_lsp_range).collect(),
+            word_pattern: None,
+        });
+
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
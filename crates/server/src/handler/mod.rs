@@ -1,18 +1,58 @@
+mod call_hierarchy;
+mod code_action;
+mod code_lens;
 mod completion;
 mod did_change;
+mod did_change_configuration;
+mod did_change_watched_files;
 mod did_close;
 mod did_open;
 mod did_save;
+mod document_highlight;
+mod document_link;
 mod document_symbol;
+mod execute_command;
 mod format;
+mod goto_declaration;
 mod goto_definition;
+mod goto_implementation;
+mod goto_type_definition;
+mod inlay_hint;
+mod linked_editing_range;
+mod on_type_formatting;
 mod publish_diagnostics;
 mod references;
 mod rename;
+mod specifiers;
+mod type_hierarchy;
+mod workspace_symbol;
 
 pub use self::{
-    completion::completion, did_change::did_change, did_close::did_close, did_open::did_open,
-    did_save::did_save, document_symbol::document_symbol, format::format,
-    goto_definition::goto_definition, publish_diagnostics::publish_diagnostics,
-    references::references, rename::rename,
+    call_hierarchy::{incoming_calls, outgoing_calls, prepare_call_hierarchy},
+    code_action::code_action,
+    code_lens::{code_lens, code_lens_resolve},
+    completion::{completion, completion_resolve},
+    did_change::did_change,
+    did_change_configuration::did_change_configuration,
+    did_change_watched_files::did_change_watched_files,
+    did_close::did_close,
+    did_open::did_open,
+    did_save::did_save,
+    document_highlight::document_highlight,
+    document_link::{document_link, document_link_resolve},
+    document_symbol::document_symbol,
+    execute_command::execute_command,
+    format::{format, range_format},
+    goto_declaration::goto_declaration,
+    goto_definition::goto_definition,
+    goto_implementation::goto_implementation,
+    goto_type_definition::goto_type_definition,
+    inlay_hint::{inlay_hint, inlay_hint_resolve},
+    linked_editing_range::linked_editing_range,
+    on_type_formatting::on_type_formatting,
+    publish_diagnostics::publish_diagnostics,
+    references::references,
+    rename::{prepare_rename, rename},
+    type_hierarchy::{prepare_type_hierarchy, subtypes, supertypes},
+    workspace_symbol::{workspace_symbol, workspace_symbol_resolve},
 };
@@ -0,0 +1,65 @@
+use std::collections::HashMap;
+
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{DocumentOnTypeFormattingParams, TextEdit};
+use queries::indents::{build_indent_captures, get_indent, get_indent_edit, split_lines};
+
+use crate::global_state::GlobalState;
+
+/// Fix the indentation of the line the character was typed on.
+pub fn on_type_formatting(
+    id: RequestId,
+    params: DocumentOnTypeFormattingParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got on type formatting request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position.text_document.uri;
+    let row = params.text_document_position.position.line as usize;
+    let options = params.options;
+
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+
+    // languages without an indents query are left alone
+    let lines = split_lines(source_code);
+    let text_edits: Vec<TextEdit> =
+        match build_indent_captures(source_code, &tree.root_node(), language_id) {
+            Ok(captures) => get_indent(
+                &captures,
+                source_code,
+                &lines,
+                &tree.root_node(),
+                row,
+                options.tab_size as usize,
+                &HashMap::new(),
+            )
+            .and_then(|indent| {
+                get_indent_edit(
+                    &lines,
+                    row,
+                    indent,
+                    options.tab_size as usize,
+                    options.insert_spaces,
+                )
+            })
+            .into_iter()
+            .collect(),
+            Err(e) => {
+                debug!("no indentation for {}: {}", language_id, e);
+                vec![]
+            }
+        };
+
+    let result = Some(text_edits);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
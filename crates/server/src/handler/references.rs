@@ -1,69 +1,97 @@
-use helper::tree_walker::get_named_node_by_position;
+use std::path::PathBuf;
+
+use helper::{convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position};
 use lsp_server::{ErrorCode::ParseError, RequestId, Response};
-use lsp_types::ReferenceParams;
-use queries::utils::get_smallest_scope_id_by_node;
+use lsp_types::{Location, Position, ReferenceParams, Url};
+use queries::locals::{build_top_level_occurrences, find_definition, is_top_level_reference};
+use tree_sitter::Range;
 
-use crate::global_state::GlobalState;
+use crate::{
+    global_state::{is_same_language, GlobalState},
+    handler::document_link::get_link_targets,
+};
 
 pub fn references(id: RequestId, params: ReferenceParams, state: GlobalState) -> Response {
     let uri = params.text_document_position.text_document.uri;
     let position = params.text_document_position.position;
-    let tree = if let Some(tree) = state.get_tree(&uri) {
+
+    let locations = match find_references(&uri, position, &state) {
+        Ok(locations) => locations,
+        Err(message) => {
+            return Response::new_err(id, ParseError as i32, message.to_string());
+        }
+    };
+
+    let result = Some(locations);
+    let result = serde_json::to_value(&result).unwrap();
+    lsp_server::Response {
+        id,
+        result: Some(result),
+        error: None,
+    }
+}
+
+/// Find every occurrence of the binding at the position, the definition first.
+/// Top-level bindings are found in every file of the same language.
+pub(crate) fn find_references(
+    uri: &Url,
+    position: Position,
+    state: &GlobalState,
+) -> Result<Vec<Location>, &'static str> {
+    let tree = if let Some(tree) = state.get_tree(uri) {
         tree
     } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No tree found for this document".to_string(),
-        );
+        return Err("No tree found for this document");
     };
     let node = if let Some(node) = get_named_node_by_position(tree, position) {
         node
     } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No node found for this position".to_string(),
-        );
+        return Err("No node found for this position");
     };
 
-    let properties = if let Some(properties) = state.sources.get(&uri) {
+    let properties = if let Some(properties) = state.sources.get(uri) {
         properties
     } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No properties found for this document".to_string(),
-        );
+        return Err("No properties found for this document");
     };
 
     let loopup_table = &properties.definitions_lookup_map;
-    let smallest_scope_id = get_smallest_scope_id_by_node(&node, &properties.ordered_scopes);
 
-    let variable_name = if let Ok(variable_name) = node.utf8_text(&properties.source_code) {
-        variable_name
-    } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No variable name found for this node".to_string(),
-        );
+    // e.g. a missing identifier inserted by the parser
+    let variable_name = match node.utf8_text(&properties.source_code) {
+        Ok(variable_name) if !variable_name.is_empty() => variable_name,
+        _ => return Err("No variable name found for this node"),
     };
 
-    let key = format!("{}:{}", variable_name, smallest_scope_id);
-    let definitions = if let Some(definitions) = loopup_table.get(&key) {
+    let definitions = find_definition(
+        loopup_table,
+        variable_name,
+        &node,
+        &properties.ordered_scopes,
+    );
+
+    if is_top_level_reference(
+        loopup_table,
+        variable_name,
+        &node,
+        &properties.ordered_scopes,
+        &tree.root_node(),
+    ) {
+        let locations =
+            find_top_level_references(uri, variable_name, &properties.language_id, state);
+        if !locations.is_empty() {
+            return Ok(locations);
+        }
+    }
+
+    let definitions = if let Some(definitions) = definitions {
         definitions
     } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No definitions found for this variable".to_string(),
-        );
+        return Err("No definitions found for this variable");
     };
 
     let mut locations = Vec::new();
 
-    // Only can go to current files
     for symbol in definitions.iter() {
         let location = lsp_types::Location {
             uri: uri.clone(),
@@ -81,11 +109,84 @@ pub fn references(id: RequestId, params: ReferenceParams, state: GlobalState) ->
         locations.push(location);
     }
 
-    let result = Some(locations);
-    let result = serde_json::to_value(&result).unwrap();
-    lsp_server::Response {
-        id,
-        result: Some(result),
-        error: None,
+    Ok(locations)
+}
+
+/// Find the occurrences of a top-level binding in the document and the files related to it, the
+/// definitions first and those of the document before those of other files. Empty if no file
+/// defines it.
+fn find_top_level_references(
+    uri: &Url,
+    variable_name: &str,
+    language_id: &str,
+    state: &GlobalState,
+) -> Vec<Location> {
+    let mut files = state.files_mentioning(variable_name, language_id);
+    if let Some((_, tree, source_code, _)) = files.iter().find(|(file_uri, ..)| *file_uri == uri) {
+        let document_targets = get_link_targets(state, uri, tree, source_code, language_id);
+        files.retain(|(file_uri, tree, source_code, file_language_id)| {
+            *file_uri == uri
+                || is_related_file(
+                    uri,
+                    &document_targets,
+                    file_uri,
+                    &get_link_targets(state, file_uri, tree, source_code, file_language_id),
+                    language_id,
+                )
+        });
+    }
+    // the document first
+    files.sort_by_key(|(file_uri, ..)| *file_uri != uri);
+
+    let mut definitions: Vec<Location> = Vec::new();
+    let mut references: Vec<Location> = Vec::new();
+    for (file_uri, tree, source_code, file_language_id) in files {
+        let (file_definitions, file_references) = build_top_level_occurrences(
+            source_code,
+            &tree.root_node(),
+            file_language_id,
+            variable_name,
+        );
+        let to_location = |range: &Range| Location {
+            uri: file_uri.clone(),
+            range: ts_range_to_lsp_range(range),
+        };
+        definitions.extend(file_definitions.iter().map(to_location));
+        references.extend(file_references.iter().map(to_location));
+    }
+
+    if definitions.is_empty() {
+        return Vec::new();
+    }
+    definitions.extend(references);
+    definitions
+}
+
+/// Whether a file may refer to the top-level bindings of the document, or the other way around:
+/// one includes or imports the other, both include the same header, or both are in the same Go
+/// package.
+fn is_related_file(
+    uri: &Url,
+    document_targets: &[PathBuf],
+    file_uri: &Url,
+    file_targets: &[PathBuf],
+    language_id: &str,
+) -> bool {
+    let (document_path, file_path) = match (uri.to_file_path(), file_uri.to_file_path()) {
+        (Ok(document_path), Ok(file_path)) => (
+            document_path.canonicalize().unwrap_or(document_path),
+            file_path.canonicalize().unwrap_or(file_path),
+        ),
+        _ => return false,
+    };
+
+    if language_id == "go" {
+        return document_path.parent() == file_path.parent();
     }
+    document_targets.contains(&file_path)
+        || file_targets.contains(&document_path)
+        || (is_same_language(language_id, "c")
+            && document_targets
+                .iter()
+                .any(|target| file_targets.contains(target)))
 }
@@ -1,23 +1,30 @@
 use std::collections::HashMap;
 
-use helper::{tree_walker::get_named_node_by_position, types::Symbol};
+use helper::{
+    convert::{lsp_range_to_ts_range, ts_range_to_lsp_range},
+    tree_walker::get_named_node_by_position,
+};
 use lsp_server::{ErrorCode::ParseError, RequestId, Response};
-use lsp_types::{RenameParams, TextEdit, Url, WorkspaceEdit};
-use queries::utils::get_smallest_scope_id_by_node;
+use lsp_types::{
+    DocumentChanges, Location, OneOf, OptionalVersionedTextDocumentIdentifier,
+    PrepareRenameResponse, RenameParams, TextDocumentEdit, TextDocumentPositionParams, TextEdit,
+    Url, WorkspaceEdit,
+};
+use queries::locals::{find_definition, find_rename_conflicts};
+use tree_sitter::{Node, Range};
 
-use crate::global_state::GlobalState;
+use super::references::find_references;
+use crate::global_state::{is_same_language, GlobalState};
 
-/// Setp:
-/// 1. Find the smallest scope id of the node
-/// 2. Find all nodes in the smallest scope id
-/// 3. Rename the node
-/// 4. Send the response
-pub fn rename(id: RequestId, params: RenameParams, state: GlobalState) -> Response {
-    // uri, position, tree, node
-    let uri = params.text_document_position.text_document.uri;
-    let position = params.text_document_position.position;
-    let tree = if let Some(tree) = state.get_tree(&uri) {
-        tree
+/// Get the range and the name of the binding at the position, if it can be renamed.
+pub fn prepare_rename(
+    id: RequestId,
+    params: TextDocumentPositionParams,
+    state: GlobalState,
+) -> Response {
+    let uri = params.text_document.uri;
+    let (tree, source_code, _) = if let Some(file) = state.get_file(&uri) {
+        file
     } else {
         return Response::new_err(
             id,
@@ -25,7 +32,7 @@ pub fn rename(id: RequestId, params: RenameParams, state: GlobalState) -> Respon
             "No tree found for this document".to_string(),
         );
     };
-    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+    let node = if let Some(node) = get_named_node_by_position(tree, params.position) {
         node
     } else {
         return Response::new_err(
@@ -35,45 +42,95 @@ pub fn rename(id: RequestId, params: RenameParams, state: GlobalState) -> Respon
         );
     };
 
-    // properties
-    let properties = if let Some(properties) = state.sources.get(&uri) {
-        properties
-    } else {
+    // keywords, literals and names without a binding can't be renamed, the occurrences are only
+    // looked for when renaming
+    let name = node.utf8_text(source_code).unwrap_or_default();
+    if !is_binding(&uri, &node, name, &state) {
         return Response::new_err(
             id,
             ParseError as i32,
-            "No properties found for this document".to_string(),
+            "The element can't be renamed".to_string(),
         );
-    };
+    }
 
-    let loopup_table = &properties.definitions_lookup_map;
-    let smallest_scope_id = get_smallest_scope_id_by_node(&node, &properties.ordered_scopes);
+    let result = Some(PrepareRenameResponse::RangeWithPlaceholder {
+        range: ts_range_to_lsp_range(&node.range()),
+        placeholder: name.to_string(),
+    });
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
 
-    let variable_name = if let Ok(variable_name) = node.utf8_text(&properties.source_code) {
-        variable_name
-    } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No variable name found for this node".to_string(),
-        );
+/// Whether the node is a name bound in the document, or defined at the top level of a file of the
+/// same language.
+fn is_binding(uri: &Url, node: &Node, name: &str, state: &GlobalState) -> bool {
+    let properties = match state.sources.get(uri) {
+        Some(properties) => properties,
+        None => return false,
     };
+    let is_identifier = node.kind().contains("identifier") || node.kind() == "name";
+    if !is_identifier
+        || name.is_empty()
+        || properties.keywords.iter().any(|keyword| keyword == name)
+    {
+        return false;
+    }
 
-    // construct key in the lookup table
-    let key = format!("{}:{}", variable_name, smallest_scope_id);
-    let definitions = if let Some(definitions) = loopup_table.get(&key) {
-        definitions
-    } else {
-        return Response::new_err(
-            id,
-            ParseError as i32,
-            "No definitions found for this variable".to_string(),
-        );
+    if find_definition(
+        &properties.definitions_lookup_map,
+        name,
+        node,
+        &properties.ordered_scopes,
+    )
+    .is_some()
+    {
+        return true;
+    }
+
+    // opened documents outside of the workspace are not indexed
+    let is_indexed = state.workspace_index.files.values().any(|file| {
+        is_same_language(&properties.language_id, &file.language_id)
+            && file.symbols.iter().any(|symbol| symbol.name == name)
+    });
+    is_indexed
+        || state.sources.values().any(|other| {
+            is_same_language(&properties.language_id, &other.language_id)
+                && other.definitions_lookup_map.contains_key(&format!(
+                    "{}:{}",
+                    name,
+                    other.ordered_scopes.len()
+                ))
+        })
+}
+
+/// Step:
+/// 1. Check the new name is an identifier of the language
+/// 2. Find all occurrences of the binding, in every file for top-level bindings
+/// 3. Check the new name conflicts with no binding
+/// 4. Rename the occurrences
+/// 5. Send the response
+pub fn rename(id: RequestId, params: RenameParams, state: GlobalState) -> Response {
+    let uri = params.text_document_position.text_document.uri;
+    let position = params.text_document_position.position;
+
+    if let Err(message) = check_new_name(&uri, &params.new_name, &state) {
+        return Response::new_err(id, ParseError as i32, message);
+    }
+
+    let locations = match find_references(&uri, position, &state) {
+        Ok(locations) => locations,
+        Err(message) => {
+            return Response::new_err(id, ParseError as i32, message.to_string());
+        }
     };
 
+    if let Err(message) = check_conflicts(&state, &locations, &params.new_name) {
+        return Response::new_err(id, ParseError as i32, message);
+    }
+
     // Found all the locations, construct the response
 
-    let result = Some(get_response(uri, definitions, params.new_name));
+    let result = Some(get_response(&state, locations, params.new_name));
 
     let result = serde_json::to_value(&result).unwrap();
     lsp_server::Response {
@@ -83,27 +140,117 @@ pub fn rename(id: RequestId, params: RenameParams, state: GlobalState) -> Respon
     }
 }
 
-fn get_response(url: Url, definitions: &[Symbol], new_text: String) -> WorkspaceEdit {
-    let mut text_edits = Vec::new();
+/// Check the new name is an identifier, and not a keyword, of the language of the document.
+fn check_new_name(uri: &Url, new_name: &str, state: &GlobalState) -> Result<(), String> {
+    let properties = match state.sources.get(uri) {
+        Some(properties) => properties,
+        None => return Err("No properties found for this document".to_string()),
+    };
+    // `$` is a letter of identifiers in javascript
+    let is_letter = |c: char| {
+        c.is_alphabetic()
+            || c == '_'
+            || (c == '$' && ["javascript", "typescript"].contains(&properties.language_id.as_str()))
+    };
 
-    for symbol in definitions.iter() {
-        let text_edit = lsp_types::TextEdit {
-            new_text: new_text.clone(),
-            range: lsp_types::Range {
-                start: lsp_types::Position {
-                    line: symbol.location.start_point.row as u32,
-                    character: symbol.location.start_point.column as u32,
-                },
-                end: lsp_types::Position {
-                    line: symbol.location.end_point.row as u32,
-                    character: symbol.location.end_point.column as u32,
-                },
-            },
+    let mut chars = new_name.chars();
+    let is_identifier =
+        chars.next().is_some_and(is_letter) && chars.all(|c| is_letter(c) || c.is_numeric());
+    if !is_identifier {
+        return Err(format!("`{}` is not a valid identifier", new_name));
+    }
+    if properties
+        .keywords
+        .iter()
+        .any(|keyword| keyword == new_name)
+    {
+        return Err(format!("`{}` is a keyword", new_name));
+    }
+    Ok(())
+}
+
+/// Check no occurrence would be bound to another binding once renamed, in every file touched.
+fn check_conflicts(
+    state: &GlobalState,
+    locations: &[Location],
+    new_name: &str,
+) -> Result<(), String> {
+    let mut uris: Vec<&Url> = locations.iter().map(|location| &location.uri).collect();
+    uris.sort();
+    uris.dedup();
+    for uri in uris {
+        let (tree, source_code, language_id) = match state.get_file(uri) {
+            Some(file) => file,
+            None => continue,
         };
-        text_edits.push(text_edit);
+        let occurrences: Vec<Range> = locations
+            .iter()
+            .filter(|location| location.uri == *uri)
+            .map(|location| lsp_range_to_ts_range(&location.range, source_code))
+            .collect();
+        let conflicts = find_rename_conflicts(
+            source_code,
+            &tree.root_node(),
+            language_id,
+            &occurrences,
+            new_name,
+        );
+        if let Some(conflict) = conflicts.first() {
+            return Err(format!(
+                "`{}` conflicts with another binding at {}:{}",
+                new_name,
+                uri,
+                conflict.start_point.row + 1
+            ));
+        }
     }
+    Ok(())
+}
+
+fn get_response(state: &GlobalState, locations: Vec<Location>, new_text: String) -> WorkspaceEdit {
+    let mut uris: Vec<Url> = Vec::new();
     let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
-    changes.insert(url, text_edits);
+    for location in locations {
+        if !changes.contains_key(&location.uri) {
+            uris.push(location.uri.clone());
+        }
+        changes.entry(location.uri).or_default().push(TextEdit {
+            new_text: new_text.clone(),
+            range: location.range,
+        });
+    }
+
+    let document_changes_support = state
+        .client_capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.workspace_edit.as_ref())
+        .and_then(|workspace_edit| workspace_edit.document_changes)
+        .unwrap_or(false);
+    if !document_changes_support {
+        return WorkspaceEdit::new(changes);
+    }
 
-    WorkspaceEdit::new(changes)
+    // versions let the client refuse the edit of a document which changed meanwhile
+    let document_changes: Vec<TextDocumentEdit> = uris
+        .into_iter()
+        .map(|uri| {
+            let version = state
+                .sources
+                .get(&uri)
+                .filter(|properties| !properties.language_id.is_empty())
+                .map(|properties| properties.version);
+            let edits = changes.remove(&uri).unwrap_or_default();
+            TextDocumentEdit {
+                text_document: OptionalVersionedTextDocumentIdentifier { uri, version },
+                edits: edits.into_iter().map(OneOf::Left).collect(),
+            }
+        })
+        .collect();
+
+    WorkspaceEdit {
+        changes: None,
+        document_changes: Some(DocumentChanges::Edits(document_changes)),
+        change_annotations: None,
+    }
 }
@@ -0,0 +1,108 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::global_state::GlobalState;
+
+/// Extensions tried for JavaScript and TypeScript specifiers, which usually omit them
+pub(crate) const SCRIPT_EXTENSIONS: [&str; 6] = ["ts", "tsx", "d.ts", "js", "jsx", "mjs"];
+
+/// Get the configured include roots, relative ones in every workspace folder.
+pub(crate) fn get_include_paths(state: &GlobalState) -> Vec<PathBuf> {
+    let roots = &state.workspace_index.roots;
+    state
+        .config
+        .include_paths
+        .iter()
+        .flat_map(|include_path| {
+            let include_path = PathBuf::from(include_path);
+            if include_path.is_absolute() {
+                vec![include_path]
+            } else {
+                roots.iter().map(|root| root.join(&include_path)).collect()
+            }
+        })
+        .collect()
+}
+
+/// Get the paths an include or import specifier of the file in `directory` names, in order of
+/// preference, whether they exist or not. Modules are named by their directory, without the
+/// extension or the file of a package, e.g. `a/b` for the Python `a.b`.
+pub(crate) fn resolve_specifier(
+    state: &GlobalState,
+    directory: &Path,
+    kind: &str,
+    language_id: &str,
+    specifier: &str,
+) -> Vec<PathBuf> {
+    let roots = &state.workspace_index.roots;
+    let include_paths = get_include_paths(state);
+
+    match kind {
+        // packages are only resolved by relative specifiers
+        "path" if matches!(language_id, "javascript" | "typescript") => {
+            if !specifier.starts_with('.') {
+                return vec![];
+            }
+            vec![directory.join(specifier)]
+        }
+        "path" => std::iter::once(directory.to_path_buf())
+            .chain(include_paths)
+            .map(|include_path| include_path.join(specifier))
+            .collect(),
+        "system" => include_paths
+            .iter()
+            .map(|include_path| include_path.join(specifier))
+            .collect(),
+        // `a::b`
+        "module" if language_id == "hare" => {
+            let module_path: PathBuf = specifier.split("::").collect();
+            roots
+                .iter()
+                .cloned()
+                .chain(include_paths)
+                .map(|root| root.join(&module_path))
+                .collect()
+        }
+        // `a.b` or `..a`
+        "module" => {
+            let name = specifier.trim_start_matches('.');
+            let level = specifier.len() - name.len();
+            let bases: Vec<PathBuf> = if level > 0 {
+                directory
+                    .ancestors()
+                    .nth(level - 1)
+                    .map(|base| vec![base.to_path_buf()])
+                    .unwrap_or_default()
+            } else {
+                std::iter::once(directory.to_path_buf())
+                    .chain(roots.iter().cloned())
+                    .chain(include_paths)
+                    .collect()
+            };
+
+            let module_path: PathBuf = name.split('.').filter(|part| !part.is_empty()).collect();
+            bases.iter().map(|base| base.join(&module_path)).collect()
+        }
+        // Go imports are directories named after the module of the workspace
+        "package" => roots
+            .iter()
+            .filter_map(|root| {
+                let go_mod = fs::read_to_string(root.join("go.mod")).ok()?;
+                let module = go_mod
+                    .lines()
+                    .find_map(|line| line.trim().strip_prefix("module "))?
+                    .trim()
+                    .trim_matches('"');
+                let package = specifier.strip_prefix(module)?;
+                if package.is_empty() {
+                    Some(root.to_path_buf())
+                } else {
+                    package.strip_prefix('/').map(|package| root.join(package))
+                }
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
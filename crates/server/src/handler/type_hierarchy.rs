@@ -0,0 +1,253 @@
+use helper::{
+    convert::ts_range_to_lsp_range, tree_walker::get_named_node_by_position, types::Symbol,
+};
+use log::debug;
+use lsp_server::{ErrorCode::ParseError, RequestId, Response};
+use lsp_types::{
+    SymbolKind, TypeHierarchyItem, TypeHierarchyPrepareParams, TypeHierarchySubtypesParams,
+    TypeHierarchySupertypesParams, Url,
+};
+use queries::{
+    children::build_document_symbols, hierarchy::build_type_relations, locals::find_definition,
+};
+use tree_sitter::{Range, Tree};
+
+use crate::global_state::GlobalState;
+
+/// Definitions which can be extended or implemented
+const TYPE_KINDS: [SymbolKind; 4] = [
+    SymbolKind::CLASS,
+    SymbolKind::STRUCT,
+    SymbolKind::INTERFACE,
+    SymbolKind::ENUM,
+];
+
+/// Get the type at the position, or the types the name at the position may resolve to.
+pub fn prepare_type_hierarchy(
+    id: RequestId,
+    params: TypeHierarchyPrepareParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got prepare type hierarchy request #{}: {:?}", id, params);
+
+    let uri = params.text_document_position_params.text_document.uri;
+    let position = params.text_document_position_params.position;
+
+    let (tree, source_code, language_id) = if let Some(file) = state.get_file(&uri) {
+        file
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No tree found for this document".to_string(),
+        );
+    };
+    let node = if let Some(node) = get_named_node_by_position(tree, position) {
+        node
+    } else {
+        return Response::new_err(
+            id,
+            ParseError as i32,
+            "No node found for this position".to_string(),
+        );
+    };
+
+    // on the name of a definition
+    let types = get_types(tree, source_code, language_id);
+    let items: Vec<TypeHierarchyItem> = match types
+        .iter()
+        .find(|symbol| symbol.location.start_byte == node.start_byte())
+    {
+        Some(symbol) => vec![to_type_hierarchy_item(&uri, symbol)],
+        None => {
+            let name = node.utf8_text(source_code).unwrap_or_default();
+            resolve_types(&state, &uri, node.range(), name)
+        }
+    };
+
+    let result = if items.is_empty() { None } else { Some(items) };
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the types the item extends or implements, in every known file.
+pub fn supertypes(
+    id: RequestId,
+    params: TypeHierarchySupertypesParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got supertypes request #{}: {:?}", id, params);
+
+    let item = params.item;
+    let mut supertypes: Vec<TypeHierarchyItem> = Vec::new();
+
+    // e.g. `impl Trait for Type` may be written far from the definition of the type
+    for (uri, tree, source_code, language_id) in state.get_files() {
+        for relation in build_type_relations(source_code, &tree.root_node(), language_id) {
+            if relation.subtype != item.name
+                || !resolve_types(&state, uri, relation.subtype_range, &relation.subtype)
+                    .iter()
+                    .any(|subtype| is_same_item(subtype, &item))
+            {
+                continue;
+            }
+            for supertype in
+                resolve_types(&state, uri, relation.supertype_range, &relation.supertype)
+            {
+                if !supertypes
+                    .iter()
+                    .any(|known| is_same_item(known, &supertype))
+                {
+                    supertypes.push(supertype);
+                }
+            }
+        }
+    }
+
+    let result = Some(supertypes);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the types which extend or implement the item, in every known file.
+pub fn subtypes(
+    id: RequestId,
+    params: TypeHierarchySubtypesParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got subtypes request #{}: {:?}", id, params);
+
+    let item = params.item;
+    let mut subtypes: Vec<TypeHierarchyItem> = Vec::new();
+
+    for (uri, tree, source_code, language_id) in state.get_files() {
+        for relation in build_type_relations(source_code, &tree.root_node(), language_id) {
+            // another type with the same name may be extended
+            if relation.supertype != item.name
+                || !resolve_types(&state, uri, relation.supertype_range, &relation.supertype)
+                    .iter()
+                    .any(|supertype| is_same_item(supertype, &item))
+            {
+                continue;
+            }
+            for subtype in resolve_types(&state, uri, relation.subtype_range, &relation.subtype) {
+                if !subtypes.iter().any(|known| is_same_item(known, &subtype)) {
+                    subtypes.push(subtype);
+                }
+            }
+        }
+    }
+
+    let result = Some(subtypes);
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Find the types a name may refer to, the local binding first, then every indexed type with
+/// that name.
+fn resolve_types(
+    state: &GlobalState,
+    uri: &Url,
+    range: Range,
+    name: &str,
+) -> Vec<TypeHierarchyItem> {
+    if let (Some(properties), Some((tree, source_code, language_id))) =
+        (state.sources.get(uri), state.get_file(uri))
+    {
+        let definition = tree
+            .root_node()
+            .named_descendant_for_byte_range(range.start_byte, range.end_byte)
+            .and_then(|node| {
+                find_definition(
+                    &properties.definitions_lookup_map,
+                    name,
+                    &node,
+                    &properties.ordered_scopes,
+                )
+            })
+            .and_then(|occurrences| occurrences.first());
+
+        if let Some(definition) = definition {
+            let items: Vec<TypeHierarchyItem> = get_types(tree, source_code, language_id)
+                .iter()
+                .filter(|symbol| symbol.location.start_byte == definition.location.start_byte)
+                .map(|symbol| to_type_hierarchy_item(uri, symbol))
+                .collect();
+            // an import binds the name too, the type is then defined in another file
+            if !items.is_empty() {
+                return items;
+            }
+        }
+    }
+
+    // the document may not be indexed, e.g. when it's outside of the workspace
+    let mut items: Vec<TypeHierarchyItem> = Vec::new();
+    if let Some((tree, source_code, language_id)) = state.get_file(uri) {
+        items.extend(
+            get_types(tree, source_code, language_id)
+                .iter()
+                .filter(|symbol| symbol.name == name)
+                .map(|symbol| to_type_hierarchy_item(uri, symbol)),
+        );
+    }
+
+    for (file_uri, file) in state.workspace_index.files.iter() {
+        if file_uri == uri {
+            continue;
+        }
+        if !file
+            .symbols
+            .iter()
+            .any(|symbol| symbol.name == name && symbol.symbol_kind.contains(&SymbolKind::CLASS))
+        {
+            continue;
+        }
+        let (tree, source_code, language_id) = match state.get_file(file_uri) {
+            Some(file) => file,
+            None => continue,
+        };
+        items.extend(
+            get_types(tree, source_code, language_id)
+                .iter()
+                .filter(|symbol| symbol.name == name)
+                .map(|symbol| to_type_hierarchy_item(file_uri, symbol)),
+        );
+    }
+    items
+}
+
+/// Get the classes, structs, interfaces and enums of the outline, nested ones included.
+fn get_types(tree: &Tree, source_code: &Vec<u8>, language_id: &str) -> Vec<Symbol> {
+    let mut types: Vec<Symbol> = Vec::new();
+    let mut stack = build_document_symbols(source_code, &tree.root_node(), language_id);
+    while let Some(mut symbol) = stack.pop() {
+        if let Some(children) = symbol.children.take() {
+            stack.extend(children);
+        }
+        if symbol
+            .symbol_kind
+            .iter()
+            .any(|kind| TYPE_KINDS.contains(kind))
+        {
+            types.push(symbol);
+        }
+    }
+    types
+}
+
+fn to_type_hierarchy_item(uri: &Url, symbol: &Symbol) -> TypeHierarchyItem {
+    TypeHierarchyItem {
+        name: symbol.name.clone(),
+        kind: *symbol.symbol_kind.last().unwrap(),
+        tags: None,
+        detail: symbol.detail.clone(),
+        uri: uri.clone(),
+        range: ts_range_to_lsp_range(&symbol.definition_range.unwrap_or(symbol.location)),
+        selection_range: ts_range_to_lsp_range(&symbol.location),
+        data: None,
+    }
+}
+
+fn is_same_item(a: &TypeHierarchyItem, b: &TypeHierarchyItem) -> bool {
+    a.uri == b.uri && a.selection_range == b.selection_range
+}
@@ -0,0 +1,185 @@
+use helper::{convert::ts_range_to_lsp_range, fuzzy::fuzzy_match};
+use log::debug;
+use lsp_server::{RequestId, Response};
+use lsp_types::{
+    Location, OneOf, SymbolKind, WorkspaceLocation, WorkspaceSymbol, WorkspaceSymbolParams,
+    WorkspaceSymbolResponse,
+};
+use serde_json::json;
+
+use crate::global_state::GlobalState;
+
+/// Keep the response small, the client asks again while the user types
+const MAX_WORKSPACE_SYMBOLS: usize = 256;
+
+/// Kinds a query can be restricted to, e.g. `function:parse` or `class:`, by the name before the
+/// colon
+const QUERY_KINDS: [(&str, &[SymbolKind]); 8] = [
+    ("function", &[SymbolKind::FUNCTION]),
+    ("method", &[SymbolKind::METHOD]),
+    ("class", &[SymbolKind::CLASS]),
+    ("type", &ANY_TYPE_KINDS),
+    ("field", &[SymbolKind::FIELD]),
+    ("variable", &[SymbolKind::VARIABLE]),
+    ("module", &[SymbolKind::MODULE]),
+    ("namespace", &[SymbolKind::NAMESPACE]),
+];
+
+/// Definitions of every kind of type, typedefs and aliases included
+const ANY_TYPE_KINDS: [SymbolKind; 5] = [
+    SymbolKind::CLASS,
+    SymbolKind::STRUCT,
+    SymbolKind::INTERFACE,
+    SymbolKind::ENUM,
+    SymbolKind::TYPE_PARAMETER,
+];
+
+/// Search the definitions of every indexed file. The query may start with a kind and a colon to
+/// only search the definitions of that kind, e.g. `function:parse`.
+pub fn workspace_symbol(
+    id: RequestId,
+    params: WorkspaceSymbolParams,
+    state: GlobalState,
+) -> Response {
+    debug!("got workspace symbol request #{}: {:?}", id, params);
+
+    let symbol_capabilities = state
+        .client_capabilities
+        .workspace
+        .as_ref()
+        .and_then(|workspace| workspace.symbol.as_ref());
+
+    // only the kinds the client knows about
+    let symbol_kinds: Option<&Vec<SymbolKind>> = symbol_capabilities
+        .and_then(|symbol| symbol.symbol_kind.as_ref())
+        .and_then(|symbol_kind| symbol_kind.value_set.as_ref());
+
+    // the range is resolved in workspaceSymbol/resolve when the client supports it
+    let resolve_range = symbol_capabilities
+        .and_then(|symbol| symbol.resolve_support.as_ref())
+        .map(|resolve_support| {
+            resolve_support
+                .properties
+                .iter()
+                .any(|property| property == "location.range")
+        })
+        .unwrap_or(false);
+
+    let (query_kinds, query) = parse_query(&params.query);
+
+    let mut candidates: Vec<(i64, WorkspaceSymbol)> = Vec::new();
+    for (uri, file) in state.workspace_index.files.iter() {
+        for symbol in &file.symbols {
+            let kind = *symbol.symbol_kind.last().unwrap();
+            if let Some(symbol_kinds) = symbol_kinds {
+                if !symbol_kinds.contains(&kind) {
+                    continue;
+                }
+            }
+            if query_kinds.is_some_and(|query_kinds| !query_kinds.contains(&kind)) {
+                continue;
+            }
+            let score = match fuzzy_match(query, &symbol.name) {
+                Some(score) => score,
+                None => continue,
+            };
+
+            let (location, data) = if resolve_range {
+                (
+                    OneOf::Right(WorkspaceLocation { uri: uri.clone() }),
+                    Some(json!({ "start_byte": symbol.location.start_byte })),
+                )
+            } else {
+                (
+                    OneOf::Left(Location {
+                        uri: uri.clone(),
+                        range: ts_range_to_lsp_range(&symbol.location),
+                    }),
+                    None,
+                )
+            };
+
+            candidates.push((
+                score,
+                WorkspaceSymbol {
+                    name: symbol.name.clone(),
+                    kind,
+                    tags: None,
+                    container_name: None,
+                    location,
+                    data,
+                },
+            ));
+        }
+    }
+
+    // best matches first
+    candidates.sort_by(|(a_score, a), (b_score, b)| {
+        b_score.cmp(a_score).then_with(|| a.name.cmp(&b.name))
+    });
+    let symbols: Vec<WorkspaceSymbol> = candidates
+        .into_iter()
+        .take(MAX_WORKSPACE_SYMBOLS)
+        .map(|(_, symbol)| symbol)
+        .collect();
+
+    let result = Some(WorkspaceSymbolResponse::Nested(symbols));
+    let result = serde_json::to_value(&result).unwrap();
+    Response::new_ok(id, result)
+}
+
+/// Split the kinds off the query, e.g. functions and `parse` of `function:parse`. A query without
+/// a known kind is searched as a whole.
+fn parse_query(query: &str) -> (Option<&'static [SymbolKind]>, &str) {
+    let kinds = query.split_once(':').and_then(|(kind, name)| {
+        let kind = kind.trim().to_lowercase();
+        QUERY_KINDS
+            .iter()
+            .find(|(query_kind, _)| *query_kind == kind)
+            .map(|(_, kinds)| (*kinds, name.trim_start()))
+    });
+    match kinds {
+        Some((kinds, name)) => (Some(kinds), name),
+        None => (None, query),
+    }
+}
+
+/// Fill in the range of a symbol returned without one.
+pub fn workspace_symbol_resolve(
+    id: RequestId,
+    mut params: WorkspaceSymbol,
+    state: GlobalState,
+) -> Response {
+    if let OneOf::Right(WorkspaceLocation { uri }) = &params.location {
+        let start_byte = params
+            .data
+            .as_ref()
+            .and_then(|data| data.get("start_byte"))
+            .and_then(|start_byte| start_byte.as_u64());
+
+        let symbol = state.workspace_index.files.get(uri).and_then(|file| {
+            // the file may have changed since, fallback to the name
+            file.symbols
+                .iter()
+                .find(|symbol| {
+                    symbol.name == params.name
+                        && Some(symbol.location.start_byte as u64) == start_byte
+                })
+                .or_else(|| {
+                    file.symbols
+                        .iter()
+                        .find(|symbol| symbol.name == params.name)
+                })
+        });
+
+        if let Some(symbol) = symbol {
+            params.location = OneOf::Left(Location {
+                uri: uri.clone(),
+                range: ts_range_to_lsp_range(&symbol.location),
+            });
+        }
+    }
+
+    let result = serde_json::to_value(&params).unwrap();
+    Response::new_ok(id, result)
+}
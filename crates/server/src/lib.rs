@@ -24,7 +24,9 @@ pub fn server_mode() -> Result<(), Box<dyn Error + Sync + Send>> {
     let init_params: InitializeParams = serde_json::from_value(initialization_params).unwrap();
     let client_capabilities: lsp_types::ClientCapabilities = init_params.capabilities.clone();
     // debug!("Client has capabilities: {:?}", client_capabilities);
-    let caps = caps::new(client_capabilities);
+    let mut caps = serde_json::to_value(caps::new(client_capabilities)).unwrap();
+    // lsp-types has no field for it yet
+    caps["typeHierarchyProvider"] = serde_json::json!(true);
 
     let initialize_data = serde_json::json!({
         "capabilities": caps,
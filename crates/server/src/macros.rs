@@ -4,16 +4,74 @@ pub mod request {
     macro_rules! req_match {
         ($req:expr, $conn:expr, $snapshot:expr) => {
             use lsp_types::request::{
-                Completion, DocumentSymbolRequest, Formatting, GotoDefinition, References, Rename,
-                Request,
+                CallHierarchyIncomingCalls, CallHierarchyOutgoingCalls, CallHierarchyPrepare,
+                CodeActionRequest, CodeLensRequest, CodeLensResolve, Completion,
+                DocumentHighlightRequest, DocumentLinkRequest, DocumentLinkResolve,
+                DocumentSymbolRequest, ExecuteCommand, Formatting, GotoDeclaration, GotoDefinition,
+                GotoImplementation, GotoTypeDefinition, InlayHintRequest, InlayHintResolveRequest,
+                LinkedEditingRange, OnTypeFormatting, PrepareRenameRequest, RangeFormatting,
+                References, Rename, Request, ResolveCompletionItem, TypeHierarchyPrepare,
+                TypeHierarchySubtypes, TypeHierarchySupertypes, WorkspaceSymbolRequest,
+                WorkspaceSymbolResolve,
             };
             match $req.method.as_str() {
                 GotoDefinition::METHOD => req!(goto_definition, $req, $conn, $snapshot),
+                GotoDeclaration::METHOD => req!(goto_declaration, $req, $conn, $snapshot),
+                GotoImplementation::METHOD => {
+                    req!(goto_implementation, $req, $conn, $snapshot)
+                }
+                GotoTypeDefinition::METHOD => {
+                    req!(goto_type_definition, $req, $conn, $snapshot)
+                }
+                PrepareRenameRequest::METHOD => req!(prepare_rename, $req, $conn, $snapshot),
                 Rename::METHOD => req!(rename, $req, $conn, $snapshot),
                 Completion::METHOD => req!(completion, $req, $conn, $snapshot),
+                ResolveCompletionItem::METHOD => {
+                    req!(completion_resolve, $req, $conn, $snapshot)
+                }
                 DocumentSymbolRequest::METHOD => req!(document_symbol, $req, $conn, $snapshot),
+                DocumentHighlightRequest::METHOD => {
+                    req!(document_highlight, $req, $conn, $snapshot)
+                }
                 References::METHOD => req!(references, $req, $conn, $snapshot),
+                CodeActionRequest::METHOD => req!(code_action, $req, $conn, $snapshot),
+                CodeLensRequest::METHOD => req!(code_lens, $req, $conn, $snapshot),
+                CodeLensResolve::METHOD => req!(code_lens_resolve, $req, $conn, $snapshot),
+                InlayHintRequest::METHOD => req!(inlay_hint, $req, $conn, $snapshot),
+                CallHierarchyPrepare::METHOD => {
+                    req!(prepare_call_hierarchy, $req, $conn, $snapshot)
+                }
+                CallHierarchyIncomingCalls::METHOD => {
+                    req!(incoming_calls, $req, $conn, $snapshot)
+                }
+                CallHierarchyOutgoingCalls::METHOD => {
+                    req!(outgoing_calls, $req, $conn, $snapshot)
+                }
+                TypeHierarchyPrepare::METHOD => {
+                    req!(prepare_type_hierarchy, $req, $conn, $snapshot)
+                }
+                TypeHierarchySupertypes::METHOD => req!(supertypes, $req, $conn, $snapshot),
+                TypeHierarchySubtypes::METHOD => req!(subtypes, $req, $conn, $snapshot),
+                InlayHintResolveRequest::METHOD => {
+                    req!(inlay_hint_resolve, $req, $conn, $snapshot)
+                }
+                LinkedEditingRange::METHOD => {
+                    req!(linked_editing_range, $req, $conn, $snapshot)
+                }
+                DocumentLinkRequest::METHOD => req!(document_link, $req, $conn, $snapshot),
+                DocumentLinkResolve::METHOD => {
+                    req!(document_link_resolve, $req, $conn, $snapshot)
+                }
                 Formatting::METHOD => req!(format, $req, $conn, $snapshot),
+                RangeFormatting::METHOD => req!(range_format, $req, $conn, $snapshot),
+                OnTypeFormatting::METHOD => req!(on_type_formatting, $req, $conn, $snapshot),
+                WorkspaceSymbolRequest::METHOD => {
+                    req!(workspace_symbol, $req, $conn, $snapshot)
+                }
+                WorkspaceSymbolResolve::METHOD => {
+                    req!(workspace_symbol_resolve, $req, $conn, $snapshot)
+                }
+                ExecuteCommand::METHOD => req!(execute_command, $req, $conn, $snapshot),
                 _ => {
                     error!("unhandled request: {:?}", $req);
                     continue;
@@ -67,10 +125,11 @@ pub mod notification {
         ($not:expr, $conn:expr, $state:ident) => {
             use lsp_types::{
                 notification::{
-                    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
-                    DidSaveTextDocument, Notification,
+                    DidChangeConfiguration, DidChangeTextDocument, DidChangeWatchedFiles,
+                    DidCloseTextDocument, DidOpenTextDocument, DidSaveTextDocument, Notification,
                 },
-                DidChangeTextDocumentParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
+                DidChangeConfigurationParams, DidChangeTextDocumentParams,
+                DidChangeWatchedFilesParams, DidCloseTextDocumentParams, DidOpenTextDocumentParams,
                 DidSaveTextDocumentParams,
             };
             match $not.method.as_str() {
@@ -86,6 +145,30 @@ pub mod notification {
                 DidSaveTextDocument::METHOD => {
                     not!(did_save, DidSaveTextDocumentParams, $not, $conn, $state)
                 }
+                DidChangeConfiguration::METHOD => {
+                    // not about a document, every opened document may be diagnosed differently
+                    if let Ok(params) = $not
+                        .clone()
+                        .extract::<DidChangeConfigurationParams>(&$not.method)
+                    {
+                        handler::did_change_configuration(params, &mut $state);
+                        for uri in $state.get_opened_uris() {
+                            let not = handler::publish_diagnostics(uri, $state.get_snapshot());
+                            $conn.sender.send(Message::Notification(not))?;
+                        }
+                    }
+                    continue;
+                }
+                DidChangeWatchedFiles::METHOD => {
+                    // files of the workspace index, no document is diagnosed
+                    if let Ok(params) = $not
+                        .clone()
+                        .extract::<DidChangeWatchedFilesParams>(&$not.method)
+                    {
+                        handler::did_change_watched_files(params, &mut $state);
+                    }
+                    continue;
+                }
 
                 _ => {
                     error!("unhandled notification: {:?}", $not);
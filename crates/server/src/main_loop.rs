@@ -1,20 +1,35 @@
-use std::error::Error;
+use std::{error::Error, sync::mpsc, thread};
 
 use log::{debug, error, warn};
 use lsp_server::{Connection, Message};
 use lsp_types::InitializeParams;
 
-use crate::{global_state, handler, not, not_match, req, req_match};
+use crate::{caps, global_state, handler, not, not_match, req, req_match};
 
 pub fn main_loop(
     connection: Connection,
-    _params: InitializeParams,
+    params: InitializeParams,
 ) -> Result<(), Box<dyn Error + Sync + Send>> {
     warn!("starting main loop");
 
-    let mut global_state = global_state::GlobalState::new();
+    let mut global_state = global_state::GlobalState::new(&params);
+    if let Some(registration) = caps::watched_files_registration(&params.capabilities) {
+        connection.sender.send(Message::Request(registration))?;
+    }
+
+    // requests are served with the opened documents until the workspace is indexed
+    let (index_sender, index_receiver) = mpsc::channel();
+    let mut workspace_index = (*global_state.workspace_index).clone();
+    global_state.pending_watched_files = Some(vec![]);
+    thread::spawn(move || {
+        workspace_index.build();
+        let _ = index_sender.send(workspace_index.files);
+    });
 
     for msg in &connection.receiver {
+        if let Ok(files) = index_receiver.try_recv() {
+            global_state.extend_workspace_index(files);
+        }
         // debug!("got msg: {:#?}", msg);
         match msg {
             Message::Request(req) => {
@@ -0,0 +1,9 @@
+;; Calls, captured by the name of the callee
+;; @call.name is the name which is resolved to a function definition
+
+(call_expression
+  function: (identifier) @call.name)
+
+(call_expression
+  function: (field_expression
+              field: (field_identifier) @call.name))
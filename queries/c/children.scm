@@ -1,14 +1,65 @@
+;; Children of the document outline.
+;; Each definition captures its name as `@<kind>.name` and the whole
+;; definition node as `@<kind>.whole`, nesting is inferred from containment.
+
 ;; struct
-(
-  struct_specifier
-      name: (_) @struct.name
-      body: (
-          field_declaration_list (
-              field_declaration
-                  type: (_)
-                  declarator: (_) @struct.field
-          )
-      )
-)@struct.whole
-
-;; TODO: enum
+(struct_specifier
+  name: (_) @struct.name
+  body: (field_declaration_list)) @struct.whole
+
+(union_specifier
+  name: (_) @struct.name
+  body: (field_declaration_list)) @struct.whole
+
+(field_declaration
+  declarator: [
+    (field_identifier) @field.name
+    (pointer_declarator
+      declarator: (field_identifier) @field.name)
+    (array_declarator
+      declarator: (field_identifier) @field.name)
+  ]) @field.whole
+
+;; enum
+(enum_specifier
+  name: (_) @enum.name
+  body: (enumerator_list)) @enum.whole
+
+(enumerator
+  name: (identifier) @enum_member.name) @enum_member.whole
+
+;; typedef
+(type_definition
+  declarator: (type_identifier) @type.name) @type.whole
+
+;; functions
+(function_definition
+  declarator: (function_declarator
+                declarator: (identifier) @function.name)) @function.whole
+
+(function_definition
+  declarator: (pointer_declarator
+                declarator: (function_declarator
+                              declarator: (identifier) @function.name))) @function.whole
+
+;; macros
+(preproc_def
+  name: (identifier) @constant.name) @constant.whole
+
+(preproc_function_def
+  name: (identifier) @function.name) @function.whole
+
+;; globals
+(translation_unit
+  (declaration
+    declarator: [
+      (identifier) @variable.name
+      (init_declarator
+        declarator: (identifier) @variable.name)
+      (init_declarator
+        declarator: (pointer_declarator
+                      declarator: (identifier) @variable.name))
+      (init_declarator
+        declarator: (array_declarator
+                      declarator: (identifier) @variable.name))
+    ]) @variable.whole)
@@ -0,0 +1,34 @@
+;; Declarations which have no body, e.g. prototypes, forward and extern declarations
+
+;; int f(int a);
+(declaration
+  declarator: [
+    (function_declarator
+      declarator: (identifier) @declaration)
+    (pointer_declarator
+      declarator: (function_declarator
+                    declarator: (identifier) @declaration))
+  ])
+
+;; extern int a;
+((declaration
+   (storage_class_specifier) @_extern
+   declarator: [
+     (identifier) @declaration
+     (pointer_declarator
+       declarator: (identifier) @declaration)
+     (array_declarator
+       declarator: (identifier) @declaration)
+   ])
+ (#eq? @_extern "extern"))
+
+;; struct s;
+(translation_unit
+  [
+    (struct_specifier
+      name: (type_identifier) @declaration
+      !body)
+    (union_specifier
+      name: (type_identifier) @declaration
+      !body)
+  ])
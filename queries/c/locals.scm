@@ -6,10 +6,13 @@
 
 (preproc_def
   name: (identifier) @definition.macro)
-(pointer_declarator
-  declarator: (identifier) @definition.var)
 (parameter_declaration
   declarator: (identifier) @definition.parameter)
+(parameter_declaration
+  declarator: (pointer_declarator
+                declarator: (identifier) @definition.parameter))
+(pointer_declarator
+  declarator: (identifier) @definition.var)
 (init_declarator
   declarator: (identifier) @definition.var)
 (array_declarator
@@ -21,10 +24,26 @@
   (enumerator_list
     (enumerator name: (identifier) @definition.var)))
 
+;; Types
+(struct_specifier
+  name: (type_identifier) @definition.type
+  body: (_))
+(union_specifier
+  name: (type_identifier) @definition.type
+  body: (_))
+(type_definition
+  declarator: (type_identifier) @definition.type)
+
 
 ;; goto
 (labeled_statement (statement_identifier) @definition)
 
+;; Assignments
+(assignment_expression
+  left: (identifier) @reference.write)
+(update_expression
+  argument: (identifier) @reference.write)
+
 ;; References
 (identifier) @reference
 ((field_identifier) @reference
@@ -0,0 +1,14 @@
+(call_expression
+  function: (identifier) @call.name)
+
+(call_expression
+  function: (field_expression
+              field: (field_identifier) @call.name))
+
+(call_expression
+  function: (qualified_identifier
+              name: (identifier) @call.name))
+
+(call_expression
+  function: (template_function
+              name: (identifier) @call.name))
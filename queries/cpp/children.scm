@@ -0,0 +1,85 @@
+;; namespace
+(namespace_definition
+  name: (identifier) @namespace.name) @namespace.whole
+
+;; class / struct
+(class_specifier
+  name: (_) @class.name
+  body: (field_declaration_list)) @class.whole
+
+(struct_specifier
+  name: (_) @struct.name
+  body: (field_declaration_list)) @struct.whole
+
+(union_specifier
+  name: (_) @struct.name
+  body: (field_declaration_list)) @struct.whole
+
+(field_declaration
+  declarator: [
+    (field_identifier) @field.name
+    (pointer_declarator
+      declarator: (field_identifier) @field.name)
+    (array_declarator
+      declarator: (field_identifier) @field.name)
+  ]) @field.whole
+
+;; methods declared in a class
+(field_declaration
+  declarator: (function_declarator
+                declarator: (field_identifier) @method.name)) @method.whole
+
+;; methods defined in a class
+(function_definition
+  declarator: (function_declarator
+                declarator: (field_identifier) @method.name)) @method.whole
+
+;; enum
+(enum_specifier
+  name: (_) @enum.name
+  body: (enumerator_list)) @enum.whole
+
+(enumerator
+  name: (identifier) @enum_member.name) @enum_member.whole
+
+;; typedef / using
+(type_definition
+  declarator: (type_identifier) @type.name) @type.whole
+
+(alias_declaration
+  name: (type_identifier) @type.name) @type.whole
+
+;; functions
+(function_definition
+  declarator: (function_declarator
+                declarator: [
+                  (identifier) @function.name
+                  (qualified_identifier
+                    name: (identifier) @function.name)
+                ])) @function.whole
+
+(function_definition
+  declarator: (pointer_declarator
+                declarator: (function_declarator
+                              declarator: (identifier) @function.name))) @function.whole
+
+(function_definition
+  declarator: (reference_declarator
+                (function_declarator
+                  declarator: (identifier) @function.name))) @function.whole
+
+;; macros
+(preproc_def
+  name: (identifier) @constant.name) @constant.whole
+
+(preproc_function_def
+  name: (identifier) @function.name) @function.whole
+
+;; globals
+(translation_unit
+  (declaration
+    declarator: [
+      (identifier) @variable.name
+      (init_declarator
+        declarator: (identifier) @variable.name)
+    ]) @variable.whole)
@@ -0,0 +1,66 @@
+;; Declarations which have no body, e.g. prototypes, forward and extern declarations
+
+;; int f(int a);
+(declaration
+  declarator: [
+    (function_declarator
+      declarator: (identifier) @declaration)
+    (pointer_declarator
+      declarator: (function_declarator
+                    declarator: (identifier) @declaration))
+  ])
+
+;; extern int a;
+((declaration
+   (storage_class_specifier) @_extern
+   declarator: [
+     (identifier) @declaration
+     (pointer_declarator
+       declarator: (identifier) @declaration)
+     (array_declarator
+       declarator: (identifier) @declaration)
+   ])
+ (#eq? @_extern "extern"))
+
+;; class A;
+(translation_unit
+  [
+    (class_specifier
+      name: (type_identifier) @declaration
+      !body)
+    (struct_specifier
+      name: (type_identifier) @declaration
+      !body)
+  ])
+
+(declaration_list
+  [
+    (class_specifier
+      name: (type_identifier) @declaration
+      !body)
+    (struct_specifier
+      name: (type_identifier) @declaration
+      !body)
+  ])
+
+;; class A { void f(); };
+(field_declaration
+  declarator: [
+    (function_declarator
+      declarator: [
+        (field_identifier)
+        (identifier)
+      ] @declaration)
+    (pointer_declarator
+      declarator: (function_declarator
+                    declarator: [
+                      (field_identifier)
+                      (identifier)
+                    ] @declaration))
+    (reference_declarator
+      (function_declarator
+        declarator: [
+          (field_identifier)
+          (identifier)
+        ] @declaration))
+  ])
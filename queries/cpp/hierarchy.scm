@@ -0,0 +1,24 @@
+;; Inheritance, captured by the name of the derived type and the name of one of its bases
+;; @subtype is the type which extends or implements @supertype
+
+(class_specifier
+  name: (type_identifier) @subtype
+  (base_class_clause
+    [
+      (type_identifier) @supertype
+      (qualified_identifier
+        name: (type_identifier) @supertype)
+      (template_type
+        name: (type_identifier) @supertype)
+    ]))
+
+(struct_specifier
+  name: (type_identifier) @subtype
+  (base_class_clause
+    [
+      (type_identifier) @supertype
+      (qualified_identifier
+        name: (type_identifier) @supertype)
+      (template_type
+        name: (type_identifier) @supertype)
+    ]))
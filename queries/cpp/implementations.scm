@@ -0,0 +1,34 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; class A { virtual void f(); void g() {} };
+(class_specifier
+  name: (type_identifier) @implementation.owner
+  body: (field_declaration_list
+          [
+            (field_declaration
+              declarator: (function_declarator
+                            declarator: (field_identifier) @implementation.method))
+            (function_definition
+              declarator: (function_declarator
+                            declarator: (field_identifier) @implementation.method))
+          ]))
+
+(struct_specifier
+  name: (type_identifier) @implementation.owner
+  body: (field_declaration_list
+          [
+            (field_declaration
+              declarator: (function_declarator
+                            declarator: (field_identifier) @implementation.method))
+            (function_definition
+              declarator: (function_declarator
+                            declarator: (field_identifier) @implementation.method))
+          ]))
+
+;; void A::f() {}
+(function_definition
+  declarator: (function_declarator
+                declarator: (qualified_identifier
+                              scope: (namespace_identifier) @implementation.owner
+                              name: (identifier) @implementation.method)))
@@ -62,6 +62,12 @@
 
 (lambda_expression) @scope
 
+;; Assignments
+(assignment_expression
+  left: (identifier) @reference.write)
+(update_expression
+  argument: (identifier) @reference.write)
+
 ;; Control structures
 (try_statement
   body: (_) @scope)
@@ -0,0 +1,11 @@
+;; Paths which can be opened as documents
+;; @link.path is a file path, @link.system a file path searched in the include roots only,
+;; @link.module a module name and @link.package a package import path
+
+;; #include "a.h"
+(preproc_include
+  path: (string_literal) @link.path)
+
+;; #include <a.h>
+(preproc_include
+  path: (system_lib_string) @link.system)
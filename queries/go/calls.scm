@@ -0,0 +1,6 @@
+(call_expression
+  function: (identifier) @call.name)
+
+(call_expression
+  function: (selector_expression
+              field: (field_identifier) @call.name))
@@ -0,0 +1,45 @@
+;; types
+(type_spec
+  name: (type_identifier) @struct.name
+  type: (struct_type)) @struct.whole
+
+(type_spec
+  name: (type_identifier) @interface.name
+  type: (interface_type)) @interface.whole
+
+(type_spec
+  name: (type_identifier) @type.name
+  type: [
+    (type_identifier)
+    (qualified_type)
+    (pointer_type)
+    (slice_type)
+    (array_type)
+    (map_type)
+    (channel_type)
+    (function_type)
+  ]) @type.whole
+
+(field_declaration
+  name: (field_identifier) @field.name) @field.whole
+
+(method_spec
+  name: (field_identifier) @method.name) @method.whole
+
+;; functions
+(function_declaration
+  name: (identifier) @function.name) @function.whole
+
+(method_declaration
+  name: (field_identifier) @method.name) @method.whole
+
+;; package level declarations
+(source_file
+  (var_declaration
+    (var_spec
+      name: (identifier) @variable.name) @variable.whole))
+
+(source_file
+  (const_declaration
+    (const_spec
+      name: (identifier) @constant.name) @constant.whole))
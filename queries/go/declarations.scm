@@ -0,0 +1,10 @@
+;; Declarations which have no body, e.g. the methods of interfaces
+
+;; type I interface { F() }
+(method_spec
+  name: (field_identifier) @declaration)
+
+;; func f() implemented in assembly
+(function_declaration
+  name: (identifier) @declaration
+  !body)
@@ -0,0 +1,21 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; func (t *T) F() {}
+(method_declaration
+  receiver: (parameter_list
+              (parameter_declaration
+                type: [
+                  (type_identifier) @implementation.owner
+                  (pointer_type
+                    (type_identifier) @implementation.owner)
+                ]))
+  name: (field_identifier) @implementation.method)
+
+;; type I interface { F() }
+(type_spec
+  name: (type_identifier) @implementation.owner
+  type: (interface_type
+          (method_spec_list
+            (method_spec
+              name: (field_identifier) @implementation.method))))
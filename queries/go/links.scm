@@ -0,0 +1,3 @@
+;; import "example.com/module/package"
+(import_spec
+  path: (interpreted_string_literal) @link.package)
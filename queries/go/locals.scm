@@ -15,8 +15,8 @@
 (var_spec 
   name: (identifier) @definition.var)
 
-(parameter_declaration (identifier) @definition.var)
-(variadic_parameter_declaration (identifier) @definition.var)
+(parameter_declaration (identifier) @definition.parameter)
+(variadic_parameter_declaration (identifier) @definition.parameter)
 
 (for_statement
  (range_clause
@@ -31,6 +31,13 @@
   (type_spec
     name: (type_identifier) @definition.type))
 
+;; Assignments
+(assignment_statement
+  left: (expression_list
+          (identifier) @reference.write))
+(inc_statement (identifier) @reference.write)
+(dec_statement (identifier) @reference.write)
+
 ;; reference
 (identifier) @reference
 (type_identifier) @reference
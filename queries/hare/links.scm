@@ -0,0 +1,8 @@
+;; Paths which can be opened as documents
+;; @link.module is a module name, a directory in the include roots
+
+;; use a::b;
+;; use a::b::{c, d};
+;; use alias = a::b;
+(use_directive
+  (identifier) @link.module)
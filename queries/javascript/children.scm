@@ -0,0 +1,35 @@
+(class_declaration
+  name: (_) @class.name) @class.whole
+
+(method_definition
+  name: (_) @method.name) @method.whole
+
+(function_declaration
+  name: (identifier) @function.name) @function.whole
+
+(generator_function_declaration
+  name: (identifier) @function.name) @function.whole
+
+(program
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @function.name
+        value: [(function) (arrow_function)]))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @function.name
+        value: [(function) (arrow_function)]))
+  ] @function.whole)
+
+(program
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @variable.name
+        value: [(number) (string) (object) (array) (call_expression) (new_expression)]))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @variable.name
+        value: [(number) (string) (object) (array) (call_expression) (new_expression)]))
+  ] @variable.whole)
@@ -0,0 +1,4 @@
+(class_declaration
+  name: (identifier) @subtype
+  (class_heritage
+    (identifier) @supertype))
@@ -0,0 +1,9 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; class A { f() {} }
+(class_declaration
+  name: (identifier) @implementation.owner
+  body: (class_body
+          (method_definition
+            name: (property_identifier) @implementation.method)))
@@ -0,0 +1,8 @@
+;; Names which must stay identical, every @linked capture of a match is edited together
+
+;; <Foo>...</Foo>
+(jsx_element
+  open_tag: (jsx_opening_element
+              name: (_) @linked)
+  close_tag: (jsx_closing_element
+               name: (_) @linked))
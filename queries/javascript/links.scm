@@ -0,0 +1,18 @@
+;; import a from "./a"
+(import_statement
+  source: (string) @link.path)
+
+;; export { a } from "./a"
+(export_statement
+  source: (string) @link.path)
+
+;; require("./a") and import("./a")
+(call_expression
+  function: [
+    ((identifier) @_require
+      (#eq? @_require "require"))
+    (import)
+  ]
+  arguments: (arguments
+               .
+               (string) @link.path))
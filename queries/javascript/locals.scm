@@ -31,3 +31,48 @@
 (formal_parameters
   (rest_pattern
     (identifier) @definition.parameter))
+
+;; Classes
+(class_declaration
+  name: (identifier) @definition.type)
+
+;; Assignments
+(assignment_expression
+  left: (identifier) @reference.write)
+(augmented_assignment_expression
+  left: (identifier) @reference.write)
+(update_expression
+  argument: (identifier) @reference.write)
+
+;; Declarations
+(variable_declarator
+  name: (identifier) @definition.var)
+(function_declaration
+  name: (identifier) @definition.function)
+(generator_function_declaration
+  name: (identifier) @definition.function)
+(import_specifier
+  (identifier) @definition.import .)
+(import_clause
+  (identifier) @definition.import)
+(namespace_import
+  (identifier) @definition.import)
+(catch_clause
+  parameter: (identifier) @definition.var)
+
+;; References
+(identifier) @reference
+
+;; Scopes
+[
+ (program)
+ (statement_block)
+ (arrow_function)
+ (for_statement)
+ (for_in_statement)
+ (catch_clause)
+] @scope
+; functions of every kind, their parameters are local to them
+(_
+  parameters: (formal_parameters)
+  body: (statement_block)) @scope
@@ -0,0 +1,7 @@
+(call_expression
+  (simple_identifier) @call.name)
+
+(call_expression
+  (navigation_expression
+    (navigation_suffix
+      (simple_identifier) @call.name)))
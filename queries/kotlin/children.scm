@@ -0,0 +1,38 @@
+;;; Types
+
+(class_declaration
+	(type_identifier) @class.name) @class.whole
+
+(type_alias
+	(type_identifier) @type.name) @type.whole
+
+(enum_class_body
+	(enum_entry
+		(simple_identifier) @enum_member.name) @enum_member.whole)
+
+;;; Members
+
+(class_body
+	(function_declaration
+		. (simple_identifier) @method.name) @method.whole)
+
+(class_body
+	(property_declaration
+		(variable_declaration
+			(simple_identifier) @property.name)) @property.whole)
+
+(class_declaration
+	(primary_constructor
+		(class_parameter
+			(simple_identifier) @property.name) @property.whole))
+
+;;; Top level
+
+(source_file
+	(function_declaration
+		. (simple_identifier) @function.name) @function.whole)
+
+(source_file
+	(property_declaration
+		(variable_declaration
+			(simple_identifier) @variable.name)) @variable.whole)
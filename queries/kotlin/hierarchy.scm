@@ -0,0 +1,10 @@
+(class_declaration
+  (type_identifier) @subtype
+  (delegation_specifier
+    [
+      (user_type
+        (type_identifier) @supertype)
+      (constructor_invocation
+        (user_type
+          (type_identifier) @supertype))
+    ]))
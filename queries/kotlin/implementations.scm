@@ -0,0 +1,9 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; class A { fun f() {} }
+(class_declaration
+  (type_identifier) @implementation.owner
+  (class_body
+    (function_declaration
+      (simple_identifier) @implementation.method)))
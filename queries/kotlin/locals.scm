@@ -48,6 +48,12 @@
 (variable_declaration
 	(simple_identifier) @definition.var)
 
+;;; Assignments
+
+(assignment
+	(directly_assignable_expression
+		(simple_identifier) @reference.write))
+
 ;;; Types
 
 (class_declaration
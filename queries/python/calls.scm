@@ -0,0 +1,6 @@
+(call
+  function: (identifier) @call.name)
+
+(call
+  function: (attribute
+              attribute: (identifier) @call.name))
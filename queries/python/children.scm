@@ -0,0 +1,52 @@
+(class_definition
+  name: (identifier) @class.name) @class.whole
+
+(class_definition
+  body: (block
+          (function_definition
+            name: (identifier) @method.name) @method.whole))
+
+(class_definition
+  body: (block
+          (decorated_definition
+            definition: (function_definition
+                          name: (identifier) @method.name)) @method.whole))
+
+(class_definition
+  body: (block
+          (expression_statement
+            (assignment
+              left: (identifier) @field.name)) @field.whole))
+
+;; instance attributes, `self.x = ...` in a method
+(class_definition
+  body: (block
+          (function_definition
+            parameters: (parameters
+                          . (identifier) @_self)
+            body: (block
+                    (expression_statement
+                      (assignment
+                        left: (attribute
+                                object: (identifier) @_object
+                                attribute: (identifier) @field.name))) @field.whole)))
+  (#eq? @_self @_object))
+
+(module
+  (function_definition
+    name: (identifier) @function.name) @function.whole)
+
+(module
+  (decorated_definition
+    definition: (function_definition
+                  name: (identifier) @function.name)) @function.whole)
+
+(function_definition
+  body: (block
+          (function_definition
+            name: (identifier) @function.name) @function.whole))
+
+(module
+  (expression_statement
+    (assignment
+      left: (identifier) @variable.name)) @variable.whole)
@@ -0,0 +1,8 @@
+(class_definition
+  name: (identifier) @subtype
+  superclasses: (argument_list
+                  [
+                    (identifier) @supertype
+                    (attribute
+                      attribute: (identifier) @supertype)
+                  ]))
@@ -0,0 +1,15 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; class A:
+;;     def f(self): ...
+(class_definition
+  name: (identifier) @implementation.owner
+  body: (block
+          [
+            (function_definition
+              name: (identifier) @implementation.method)
+            (decorated_definition
+              definition: (function_definition
+                            name: (identifier) @implementation.method))
+          ]))
@@ -0,0 +1,15 @@
+;; import a.b
+(import_statement
+  name: [
+    (dotted_name) @link.module
+    (aliased_import
+      name: (dotted_name) @link.module)
+  ])
+
+;; from a.b import c
+;; from ..a import b
+(import_from_statement
+  module_name: [
+    (dotted_name)
+    (relative_import)
+  ] @link.module)
@@ -104,6 +104,9 @@
    (identifier)
    (identifier) @definition.field))
 
+(augmented_assignment
+ left: (identifier) @reference.write)
+
 ; Walrus operator  x := 1
 (named_expression
   (identifier) @definition.var)
@@ -0,0 +1,13 @@
+(call_expression
+  function: (identifier) @call.name)
+
+(call_expression
+  function: (field_expression
+              field: (field_identifier) @call.name))
+
+(call_expression
+  function: (scoped_identifier
+              name: (identifier) @call.name))
+
+(macro_invocation
+  macro: (identifier) @call.name)
@@ -0,0 +1,68 @@
+; Types
+(struct_item
+  name: (type_identifier) @struct.name) @struct.whole
+
+(union_item
+  name: (type_identifier) @struct.name) @struct.whole
+
+(field_declaration
+  name: (field_identifier) @field.name) @field.whole
+
+(enum_item
+  name: (type_identifier) @enum.name) @enum.whole
+
+(enum_variant
+  name: (identifier) @enum_member.name) @enum_member.whole
+
+(trait_item
+  name: (type_identifier) @interface.name) @interface.whole
+
+(type_item
+  name: (type_identifier) @type.name) @type.whole
+
+(impl_item
+  type: (_) @impl.name) @impl.whole
+
+; Functions
+(source_file
+  (function_item
+    name: (identifier) @function.name) @function.whole)
+
+(mod_item
+  body: (declaration_list
+          (function_item
+            name: (identifier) @function.name) @function.whole))
+
+(block
+  (function_item
+    name: (identifier) @function.name) @function.whole)
+
+; Methods
+(impl_item
+  body: (declaration_list
+          (function_item
+            name: (identifier) @method.name) @method.whole))
+
+(trait_item
+  body: (declaration_list
+          [
+            (function_item
+              name: (identifier) @method.name)
+            (function_signature_item
+              name: (identifier) @method.name)
+          ] @method.whole))
+
+; Constants
+(const_item
+  name: (identifier) @constant.name) @constant.whole
+
+(static_item
+  name: (identifier) @constant.name) @constant.whole
+
+; Macros
+(macro_definition
+  name: (identifier) @function.name) @function.whole
+
+; Module
+(mod_item
+  name: (identifier) @module.name) @module.whole
@@ -0,0 +1,24 @@
+(impl_item
+  trait: [
+    (type_identifier) @supertype
+    (scoped_type_identifier
+      name: (type_identifier) @supertype)
+    (generic_type
+      type: (type_identifier) @supertype)
+  ]
+  type: [
+    (type_identifier) @subtype
+    (generic_type
+      type: (type_identifier) @subtype)
+  ])
+
+(trait_item
+  name: (type_identifier) @subtype
+  bounds: (trait_bounds
+            [
+              (type_identifier) @supertype
+              (scoped_type_identifier
+                name: (type_identifier) @supertype)
+              (generic_type
+                type: (type_identifier) @supertype)
+            ]))
@@ -0,0 +1,24 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; impl Trait for Type { fn f() {} }
+(impl_item
+  type: [
+    (type_identifier) @implementation.owner
+    (generic_type
+      type: (type_identifier) @implementation.owner)
+  ]
+  body: (declaration_list
+          (function_item
+            name: (identifier) @implementation.method)))
+
+;; trait Trait { fn f(); }
+(trait_item
+  name: (type_identifier) @implementation.owner
+  body: (declaration_list
+          [
+            (function_item
+              name: (identifier) @implementation.method)
+            (function_signature_item
+              name: (identifier) @implementation.method)
+          ]))
@@ -23,7 +23,7 @@
 
 ; Variables
 (parameter 
-  pattern: (identifier) @definition.var) 
+  pattern: (identifier) @definition.parameter) 
 
 (let_declaration 
   pattern: (identifier) @definition.var)
@@ -60,6 +60,9 @@
 (enum_item
   name: (type_identifier) @definition.type)
 
+(trait_item
+  name: (type_identifier) @definition.type)
+
 
 ; Fields
 (field_declaration
@@ -68,6 +71,13 @@
 (enum_variant
   name: (identifier) @definition.field) 
 
+; Assignments
+(assignment_expression
+  left: (identifier) @reference.write)
+
+(compound_assignment_expr
+  left: (identifier) @reference.write)
+
 ; References
 (identifier) @reference
 ((type_identifier) @reference
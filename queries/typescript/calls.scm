@@ -0,0 +1,9 @@
+(call_expression
+  function: (identifier) @call.name)
+
+(call_expression
+  function: (member_expression
+              property: (property_identifier) @call.name))
+
+(new_expression
+  constructor: (identifier) @call.name)
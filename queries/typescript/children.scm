@@ -0,0 +1,59 @@
+(class_declaration
+  name: (_) @class.name) @class.whole
+
+(method_definition
+  name: (_) @method.name) @method.whole
+
+(function_declaration
+  name: (identifier) @function.name) @function.whole
+
+(generator_function_declaration
+  name: (identifier) @function.name) @function.whole
+
+(program
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @function.name
+        value: [(function) (arrow_function)]))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @function.name
+        value: [(function) (arrow_function)]))
+  ] @function.whole)
+
+(program
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @variable.name
+        value: [(number) (string) (object) (array) (call_expression) (new_expression)]))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @variable.name
+        value: [(number) (string) (object) (array) (call_expression) (new_expression)]))
+  ] @variable.whole)
+
+(public_field_definition
+  name: (_) @field.name) @field.whole
+
+(interface_declaration
+  name: (type_identifier) @interface.name) @interface.whole
+
+(method_signature
+  name: (_) @method.name) @method.whole
+
+(property_signature
+  name: (_) @property.name) @property.whole
+
+(enum_declaration
+  name: (identifier) @enum.name) @enum.whole
+
+(type_alias_declaration
+  name: (type_identifier) @type.name) @type.whole
+
+(internal_module
+  name: (_) @namespace.name) @namespace.whole
+
+(abstract_class_declaration
+  name: (type_identifier) @class.name) @class.whole
@@ -0,0 +1,43 @@
+;; Declarations which have no body, e.g. ambient declarations and overload signatures
+
+;; declare function f(): void;
+(ambient_declaration
+  (function_signature
+    name: (identifier) @declaration))
+
+;; declare const a: number;
+(ambient_declaration
+  [
+    (lexical_declaration
+      (variable_declarator
+        name: (identifier) @declaration))
+    (variable_declaration
+      (variable_declarator
+        name: (identifier) @declaration))
+  ])
+
+;; declare class A {}
+(ambient_declaration
+  [
+    (class_declaration
+      name: (type_identifier) @declaration)
+    (abstract_class_declaration
+      name: (type_identifier) @declaration)
+  ])
+
+;; function f(a: string): void;
+(program
+  (function_signature
+    name: (identifier) @declaration))
+
+(export_statement
+  (function_signature
+    name: (identifier) @declaration))
+
+;; abstract f(): void;
+(abstract_method_signature
+  name: (property_identifier) @declaration)
+
+;; interface I { f(): void; }
+(method_signature
+  name: (property_identifier) @declaration)
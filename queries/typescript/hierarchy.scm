@@ -0,0 +1,30 @@
+(class_declaration
+  name: (type_identifier) @subtype
+  (class_heritage
+    (extends_clause
+      value: (identifier) @supertype)))
+
+(class_declaration
+  name: (type_identifier) @subtype
+  (class_heritage
+    (implements_clause
+      [
+        (type_identifier) @supertype
+        (generic_type
+          name: (type_identifier) @supertype)
+      ])))
+
+(abstract_class_declaration
+  name: (type_identifier) @subtype
+  (class_heritage
+    (extends_clause
+      value: (identifier) @supertype)))
+
+(interface_declaration
+  name: (type_identifier) @subtype
+  (extends_type_clause
+    [
+      (type_identifier) @supertype
+      (generic_type
+        name: (type_identifier) @supertype)
+    ]))
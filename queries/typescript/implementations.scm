@@ -0,0 +1,31 @@
+;; Methods by the type which owns them
+;; @implementation.owner is the name of the type, @implementation.method the name of the method
+
+;; class A { f() {} }
+([
+  (class_declaration
+    name: (type_identifier) @implementation.owner
+    body: (class_body
+            [
+              (method_definition
+                name: (property_identifier) @implementation.method)
+              (method_signature
+                name: (property_identifier) @implementation.method)
+            ]))
+  (abstract_class_declaration
+    name: (type_identifier) @implementation.owner
+    body: (class_body
+            [
+              (method_definition
+                name: (property_identifier) @implementation.method)
+              (abstract_method_signature
+                name: (property_identifier) @implementation.method)
+            ]))
+ ])
+
+;; interface I { f(): void; }
+(interface_declaration
+  name: (type_identifier) @implementation.owner
+  body: (interface_body
+          (method_signature
+            name: (property_identifier) @implementation.method)))
@@ -1,6 +1,6 @@
 ; inherits: ecma
-(required_parameter (identifier) @definition)
-(optional_parameter (identifier) @definition)
+(required_parameter (identifier) @definition.parameter)
+(optional_parameter (identifier) @definition.parameter)
 
 ; x => x
 (arrow_function
@@ -25,3 +25,58 @@
 (required_parameter
   (rest_pattern
     (identifier) @definition.parameter))
+
+;; Types
+(class_declaration
+  name: (type_identifier) @definition.type)
+(abstract_class_declaration
+  name: (type_identifier) @definition.type)
+(interface_declaration
+  name: (type_identifier) @definition.type)
+
+;; Assignments
+(assignment_expression
+  left: (identifier) @reference.write)
+(augmented_assignment_expression
+  left: (identifier) @reference.write)
+(update_expression
+  argument: (identifier) @reference.write)
+
+;; Declarations
+(variable_declarator
+  name: (identifier) @definition.var)
+(function_declaration
+  name: (identifier) @definition.function)
+(generator_function_declaration
+  name: (identifier) @definition.function)
+(import_specifier
+  (identifier) @definition.import .)
+(import_clause
+  (identifier) @definition.import)
+(namespace_import
+  (identifier) @definition.import)
+(catch_clause
+  parameter: (identifier) @definition.var)
+(type_alias_declaration
+  name: (type_identifier) @definition.type)
+(enum_declaration
+  name: (identifier) @definition.type)
+
+;; References
+(identifier) @reference
+((type_identifier) @reference
+                   (set! reference.kind "type"))
+
+;; Scopes
+[
+ (program)
+ (statement_block)
+ (arrow_function)
+ (for_statement)
+ (for_in_statement)
+ (catch_clause)
+] @scope
+; functions of every kind, their parameters are local to them
+(_
+  parameters: (formal_parameters)
+  body: (statement_block)) @scope